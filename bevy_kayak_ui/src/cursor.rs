@@ -0,0 +1,17 @@
+use kayak_core::CursorIcon;
+
+pub fn convert_cursor_icon(cursor_icon: CursorIcon) -> bevy::window::CursorIcon {
+    match cursor_icon {
+        CursorIcon::Default => bevy::window::CursorIcon::Default,
+        CursorIcon::Pointer => bevy::window::CursorIcon::Hand,
+        CursorIcon::Text => bevy::window::CursorIcon::Text,
+        CursorIcon::Grab => bevy::window::CursorIcon::Grab,
+        CursorIcon::Grabbing => bevy::window::CursorIcon::Grabbing,
+        CursorIcon::Move => bevy::window::CursorIcon::Move,
+        CursorIcon::ResizeHorizontal => bevy::window::CursorIcon::EwResize,
+        CursorIcon::ResizeVertical => bevy::window::CursorIcon::NsResize,
+        CursorIcon::ResizeNwse => bevy::window::CursorIcon::NwseResize,
+        CursorIcon::ResizeNesw => bevy::window::CursorIcon::NeswResize,
+        CursorIcon::NotAllowed => bevy::window::CursorIcon::NotAllowed,
+    }
+}
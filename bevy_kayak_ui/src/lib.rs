@@ -1,21 +1,32 @@
 use bevy::{
-    input::{keyboard::KeyboardInput, mouse::MouseButtonInput, ElementState},
+    input::{
+        gamepad::{GamepadButton, GamepadButtonType},
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel},
+        touch::{TouchInput, TouchPhase},
+        ElementState, Input,
+    },
     math::Vec2,
-    prelude::{EventReader, IntoExclusiveSystem, MouseButton, Plugin, Res, World},
+    prelude::{
+        AddAsset, EventReader, IntoExclusiveSystem, Local, MouseButton, Plugin, Res, ResMut, World,
+    },
     render::color::Color,
     window::{CursorMoved, ReceivedCharacter, WindowCreated, WindowResized, Windows},
 };
 
 mod bevy_context;
 mod camera;
+mod cursor;
 mod key;
 mod render;
+mod stylesheet;
 
 pub use bevy_context::BevyContext;
 pub use camera::*;
-use kayak_core::{bind, Binding, InputEvent, MutableBound};
+use kayak_core::{bind, Binding, CursorIcon, InputEvent, MutableBound, NavigationDirection};
 pub use render::unified::font::FontMapping;
 pub use render::unified::image::ImageManager;
+pub use stylesheet::{StyleSheetAsset, StyleSheetLoader, UiStyleSheet};
 
 #[derive(Default)]
 pub struct BevyKayakUIPlugin;
@@ -25,8 +36,12 @@ impl Plugin for BevyKayakUIPlugin {
         app.insert_resource(bind(WindowSize::default()))
             .add_plugin(render::BevyKayakUIRenderPlugin)
             .add_plugin(camera::KayakUICameraPlugin)
+            .add_asset::<StyleSheetAsset>()
+            .add_asset_loader(StyleSheetLoader)
             .add_system(update_window_size)
             .add_system(process_events)
+            .add_system(update_cursor_icon)
+            .add_system(stylesheet::update_stylesheet)
             .add_system(update.exclusive_system());
     }
 }
@@ -46,13 +61,21 @@ pub fn update(world: &mut World) {
     world.insert_resource(bevy_context);
 }
 
+/// The number of pixels scrolled per line for mouse wheels that scroll by lines.
+///
+/// The sign of the scroll delta is kept as reported by bevy: positive `y` scrolls up.
+const SCROLL_LINE_HEIGHT: f32 = 20.0;
+
 pub fn process_events(
     bevy_context: Res<BevyContext>,
     windows: Res<Windows>,
     mut cursor_moved_events: EventReader<CursorMoved>,
     mut mouse_button_input_events: EventReader<MouseButtonInput>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
     mut char_input_events: EventReader<ReceivedCharacter>,
     mut keyboard_input_events: EventReader<KeyboardInput>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut touch_input_events: EventReader<TouchInput>,
 ) {
     let window_size = if let Some(window) = windows.get_primary() {
         Vec2::new(window.width(), window.height())
@@ -80,10 +103,50 @@ pub fn process_events(
                         input_events.push(InputEvent::MouseLeftRelease);
                     }
                 }
+                MouseButton::Right => {
+                    if event.state == ElementState::Pressed {
+                        input_events.push(InputEvent::MouseRightPress);
+                    } else if event.state == ElementState::Released {
+                        input_events.push(InputEvent::MouseRightRelease);
+                    }
+                }
+                MouseButton::Middle => {
+                    if event.state == ElementState::Pressed {
+                        input_events.push(InputEvent::MouseMiddlePress);
+                    } else if event.state == ElementState::Released {
+                        input_events.push(InputEvent::MouseMiddleRelease);
+                    }
+                }
                 _ => {}
             }
         }
 
+        // Scrolling is accumulated into a single event per frame
+        let (mut delta_x, mut delta_y) = (0.0, 0.0);
+        for event in mouse_wheel_events.iter() {
+            let scale = match event.unit {
+                MouseScrollUnit::Line => SCROLL_LINE_HEIGHT,
+                MouseScrollUnit::Pixel => 1.0,
+            };
+            delta_x += event.x * scale;
+            delta_y += event.y * scale;
+        }
+        if delta_x != 0.0 || delta_y != 0.0 {
+            input_events.push(InputEvent::Scroll { delta_x, delta_y });
+        }
+
+        for event in touch_input_events.iter() {
+            // Like the cursor, touch positions start at the bottom of the window
+            let id = event.id;
+            let position = (event.position.x, window_size.y - event.position.y);
+            input_events.push(match event.phase {
+                TouchPhase::Started => InputEvent::TouchStart { id, position },
+                TouchPhase::Moved => InputEvent::TouchMove { id, position },
+                TouchPhase::Ended => InputEvent::TouchEnd { id, position },
+                TouchPhase::Cancelled => InputEvent::TouchCancel { id },
+            });
+        }
+
         for event in char_input_events.iter() {
             input_events.push(InputEvent::CharEvent { c: event.char });
         }
@@ -93,14 +156,53 @@ pub fn process_events(
                 let kayak_key_code = key::convert_virtual_key_code(key_code);
                 input_events.push(InputEvent::Keyboard {
                     key: kayak_key_code,
+                    is_pressed: event.state == ElementState::Pressed,
                 });
             }
         }
 
+        for button in gamepad_buttons.get_just_pressed() {
+            if let Some(input_event) = convert_gamepad_button(button.1) {
+                input_events.push(input_event);
+            }
+        }
+
         context.process_events(input_events);
     }
 }
 
+/// Shows the cursor icon of the widget under the mouse on the primary window
+pub fn update_cursor_icon(
+    bevy_context: Res<BevyContext>,
+    mut windows: ResMut<Windows>,
+    mut current_cursor_icon: Local<Option<CursorIcon>>,
+) {
+    if let Ok(context) = bevy_context.kayak_context.read() {
+        let cursor_icon = context.cursor_icon();
+        // Only changes are applied so that the window isn't updated every frame
+        if *current_cursor_icon != Some(cursor_icon) {
+            if let Some(window) = windows.get_primary_mut() {
+                window.set_cursor_icon(cursor::convert_cursor_icon(cursor_icon));
+                *current_cursor_icon = Some(cursor_icon);
+            }
+        }
+    }
+}
+
+/// Maps gamepad buttons to navigation: the D-pad moves focus, South (A on Xbox controllers) confirms and East
+/// (B on Xbox controllers) goes back.
+fn convert_gamepad_button(button_type: GamepadButtonType) -> Option<InputEvent> {
+    match button_type {
+        GamepadButtonType::DPadUp => Some(InputEvent::Navigate(NavigationDirection::Up)),
+        GamepadButtonType::DPadDown => Some(InputEvent::Navigate(NavigationDirection::Down)),
+        GamepadButtonType::DPadLeft => Some(InputEvent::Navigate(NavigationDirection::Left)),
+        GamepadButtonType::DPadRight => Some(InputEvent::Navigate(NavigationDirection::Right)),
+        GamepadButtonType::South => Some(InputEvent::Confirm),
+        GamepadButtonType::East => Some(InputEvent::Back),
+        _ => None,
+    }
+}
+
 /// Tracks the bevy window size.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct WindowSize(pub f32, pub f32);
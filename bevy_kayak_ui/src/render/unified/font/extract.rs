@@ -1,6 +1,7 @@
 use bevy::{
     math::Vec2,
     prelude::{Assets, Res},
+    render::color::Color,
     sprite::Rect,
 };
 use kayak_core::render_primitive::RenderPrimitive;
@@ -69,6 +70,11 @@ pub fn extract_texts(
                 quad_type: UIQuadType::Text,
                 type_index: 0,
                 border_radius: (0.0, 0.0, 0.0, 0.0),
+                border: (0.0, 0.0, 0.0, 0.0),
+                border_color: Color::NONE,
+                box_shadow: None,
+                gradient: None,
+                transform: None,
                 image: None,
                 uv_max: None,
                 uv_min: None,
@@ -11,8 +11,12 @@ pub fn extract_images(
     image_manager: &Res<ImageManager>,
     dpi: f32,
 ) -> Vec<ExtractQuadBundle> {
-    let (layout, handle) = match render_command {
-        RenderPrimitive::Image { layout, handle } => (layout, handle),
+    let (layout, handle, opacity) = match render_command {
+        RenderPrimitive::Image {
+            layout,
+            handle,
+            opacity,
+        } => (layout, handle, opacity),
         _ => panic!(""),
     };
 
@@ -22,7 +26,7 @@ pub fn extract_images(
                 min: Vec2::new(layout.posx, layout.posy),
                 max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height) * dpi,
             },
-            color: Color::WHITE,
+            color: Color::rgba(1.0, 1.0, 1.0, *opacity),
             vertex_index: 0,
             char_id: 0,
             z_index: layout.z_index,
@@ -30,6 +34,11 @@ pub fn extract_images(
             quad_type: UIQuadType::Image,
             type_index: 0,
             border_radius: (0.0, 0.0, 0.0, 0.0),
+            border: (0.0, 0.0, 0.0, 0.0),
+            border_color: Color::NONE,
+            box_shadow: None,
+            gradient: None,
+            transform: None,
             image: image_manager
                 .get_handle(handle)
                 .and_then(|a| Some(a.clone_weak())),
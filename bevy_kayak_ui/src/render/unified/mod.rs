@@ -9,7 +9,7 @@ use bevy::{
     sprite::Rect,
     window::Windows,
 };
-use kayak_core::{render_primitive::RenderPrimitive, Binding, Bound};
+use kayak_core::{render_primitive::RenderPrimitive, Affine, Binding, Bound};
 use kayak_font::KayakFont;
 
 use crate::{
@@ -90,6 +90,15 @@ pub fn extract(
 
     let mut extracted_quads = Vec::new();
     for render_primitive in render_primitives {
+        // Transformed primitives are extracted like the others, their quads being transformed when prepared
+        let (render_primitive, transform) = match render_primitive {
+            RenderPrimitive::Transformed {
+                transform,
+                primitive,
+            } => (*primitive, Some(transform)),
+            render_primitive => (render_primitive, None),
+        };
+        let first_quad = extracted_quads.len();
         match render_primitive {
             RenderPrimitive::Text { .. } => {
                 let text_quads = font::extract_texts(&render_primitive, &fonts, &font_mapping, dpi);
@@ -103,6 +112,10 @@ pub fn extract(
                 let quad_quads = quad::extract_quads(&render_primitive, dpi);
                 extracted_quads.extend(quad_quads);
             }
+            RenderPrimitive::BoxShadow { .. } => {
+                let shadow_quads = quad::extract_box_shadows(&render_primitive, dpi);
+                extracted_quads.extend(shadow_quads);
+            }
             RenderPrimitive::NinePatch { .. } => {
                 let nine_patch_quads =
                     nine_patch::extract_nine_patch(&render_primitive, &image_manager, &images, dpi);
@@ -124,6 +137,11 @@ pub fn extract(
                         quad_type: UIQuadType::Clip,
                         type_index: 0,
                         border_radius: (0.0, 0.0, 0.0, 0.0),
+                        border: (0.0, 0.0, 0.0, 0.0),
+                        border_color: Color::NONE,
+                        box_shadow: None,
+                        gradient: None,
+                        transform: None,
                         image: None,
                         uv_min: None,
                         uv_max: None,
@@ -132,6 +150,16 @@ pub fn extract(
             }
             _ => {}
         }
+
+        if let Some(transform) = transform {
+            // The transform is in logical pixels while the quads are in physical pixels
+            let transform = Affine::scale(1.0 / dpi, 1.0 / dpi)
+                .then(&transform)
+                .then(&Affine::scale(dpi, dpi));
+            for quad in &mut extracted_quads[first_quad..] {
+                quad.extracted_quad.transform = Some(transform);
+            }
+        }
     }
 
     commands.insert_resource(window_size.get());
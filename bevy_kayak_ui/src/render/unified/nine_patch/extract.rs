@@ -19,12 +19,13 @@ pub fn extract_nine_patch(
 ) -> Vec<ExtractQuadBundle> {
     let mut extracted_quads = Vec::new();
 
-    let (layout, handle, border) = match render_primitive {
+    let (layout, handle, border, opacity) = match render_primitive {
         RenderPrimitive::NinePatch {
             layout,
             handle,
             border,
-        } => (layout, handle, border),
+            opacity,
+        } => (layout, handle, border, opacity),
         _ => panic!(""),
     };
 
@@ -53,7 +54,7 @@ pub fn extract_nine_patch(
             min: Vec2::ZERO,
             max: Vec2::ZERO,
         },
-        color: Color::WHITE,
+        color: Color::rgba(1.0, 1.0, 1.0, *opacity),
         vertex_index: 0,
         char_id: 0,
         z_index: layout.z_index,
@@ -61,6 +62,11 @@ pub fn extract_nine_patch(
         quad_type: UIQuadType::Image,
         type_index: 0,
         border_radius: (0.0, 0.0, 0.0, 0.0),
+        border: (0.0, 0.0, 0.0, 0.0),
+        border_color: Color::NONE,
+        box_shadow: None,
+        gradient: None,
+        transform: None,
         image: image_handle,
         uv_max: None,
         uv_min: None,
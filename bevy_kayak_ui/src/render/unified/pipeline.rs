@@ -32,6 +32,7 @@ use bevy::{
     utils::HashMap,
 };
 use bytemuck::{Pod, Zeroable};
+use kayak_core::{Affine, BoxShadow};
 use kayak_font::{
     bevy::{FontRenderingPipeline, FontTextureCache},
     KayakFont,
@@ -150,7 +151,7 @@ impl FromWorld for UnifiedPipeline {
         });
 
         let vertex_buffer_layout = VertexBufferLayout {
-            array_stride: 60,
+            array_stride: 164,
             step_mode: VertexStepMode::Vertex,
             attributes: vec![
                 VertexAttribute {
@@ -173,6 +174,41 @@ impl FromWorld for UnifiedPipeline {
                     offset: 44,
                     shader_location: 3,
                 },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 60,
+                    shader_location: 4,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 76,
+                    shader_location: 5,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 92,
+                    shader_location: 6,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 108,
+                    shader_location: 7,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 124,
+                    shader_location: 8,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 140,
+                    shader_location: 9,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: 156,
+                    shader_location: 10,
+                },
             ],
         };
 
@@ -308,6 +344,31 @@ pub enum UIQuadType {
     Text,
     Image,
     Clip,
+    BoxShadow,
+}
+
+/// The shape of a gradient, in the coordinates of the quad it fills
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientShape {
+    /// The angle is in radians, clockwise from the top
+    Linear { angle: f32 },
+    /// The center is relative to the size of the quad
+    Radial { center: Vec2 },
+}
+
+/// The part of a gradient between two of its color stops
+///
+/// Gradients are drawn with one quad per segment, each quad only drawing the fragments within its segment. The
+/// first and last segments also draw the fragments before and after the gradient.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientSegment {
+    pub shape: GradientShape,
+    /// The positions of the stops at the start and the end of the segment, whose colors are the color of the quad
+    /// and `end_color`
+    pub stops: (f32, f32),
+    pub end_color: Color,
+    /// The positions along the gradient this segment draws
+    pub range: (f32, f32),
 }
 
 #[derive(Debug, Component, Clone)]
@@ -321,6 +382,15 @@ pub struct ExtractedQuad {
     pub quad_type: UIQuadType,
     pub type_index: u32,
     pub border_radius: (f32, f32, f32, f32),
+    /// The width of the border on each side: top, right, bottom and left
+    pub border: (f32, f32, f32, f32),
+    pub border_color: Color,
+    /// The shadow drawn by [UIQuadType::BoxShadow] quads, whose rect is the one of the quad casting it
+    pub box_shadow: Option<BoxShadow>,
+    /// The part of a gradient drawn by a [UIQuadType::Quad], instead of filling it with its color
+    pub gradient: Option<GradientSegment>,
+    /// Maps the rect of the quad to where it's drawn, in physical pixels, for widgets with a `transform` style
+    pub transform: Option<Affine>,
     pub image: Option<Handle<Image>>,
     pub uv_min: Option<Vec2>,
     pub uv_max: Option<Vec2>,
@@ -333,6 +403,14 @@ struct QuadVertex {
     pub color: [f32; 4],
     pub uv: [f32; 4],
     pub pos_size: [f32; 4],
+    pub border: [f32; 4],
+    pub border_color: [f32; 4],
+    pub shadow: [f32; 4],
+    pub gradient_color: [f32; 4],
+    pub gradient_stops: [f32; 4],
+    pub gradient_shape: [f32; 4],
+    /// The position before the transform of the quad, which the shader draws the quad at
+    pub local_position: [f32; 2],
 }
 
 #[repr(C)]
@@ -377,10 +455,14 @@ pub fn prepare_quads(
     }
 
     sprite_meta.types_buffer.clear();
-    sprite_meta.types_buffer.reserve(2, &render_device);
+    sprite_meta.types_buffer.reserve(7, &render_device);
     let quad_type_offset = sprite_meta.types_buffer.push(QuadType { t: 0 });
     let text_type_offset = sprite_meta.types_buffer.push(QuadType { t: 1 });
     let image_type_offset = sprite_meta.types_buffer.push(QuadType { t: 2 });
+    let box_shadow_type_offset = sprite_meta.types_buffer.push(QuadType { t: 3 });
+    let inset_box_shadow_type_offset = sprite_meta.types_buffer.push(QuadType { t: 4 });
+    let linear_gradient_type_offset = sprite_meta.types_buffer.push(QuadType { t: 5 });
+    let radial_gradient_type_offset = sprite_meta.types_buffer.push(QuadType { t: 6 });
     sprite_meta
         .types_buffer
         .write_buffer(&render_device, &render_queue);
@@ -397,12 +479,57 @@ pub fn prepare_quads(
         .enumerate()
     {
         let sprite_rect = extracted_sprite.rect;
+        let box_shadow = extracted_sprite.box_shadow.unwrap_or_default();
+        // Shadows are drawn over the area they cover, while the shader still needs the rect of the quad casting them
+        let quad_rect = if extracted_sprite.quad_type == UIQuadType::BoxShadow && !box_shadow.inset
+        {
+            let extent = Vec2::splat(box_shadow.spread.max(0.0) + box_shadow.blur.max(0.0));
+            let offset = Vec2::new(box_shadow.offset.0, box_shadow.offset.1);
+            Rect {
+                min: sprite_rect.min + offset - extent,
+                max: sprite_rect.max + offset + extent,
+            }
+        } else {
+            sprite_rect
+        };
         let color = extracted_sprite.color.as_linear_rgba_f32();
+        let border_color = extracted_sprite.border_color.as_linear_rgba_f32();
+        let (border_top, border_right, border_bottom, border_left) = extracted_sprite.border;
+        let (gradient_color, gradient_stops, gradient_shape) = match extracted_sprite.gradient {
+            Some(gradient) => {
+                let shape = match gradient.shape {
+                    GradientShape::Linear { angle } => [angle, 0.0, 0.0, 0.0],
+                    GradientShape::Radial { center } => [center.x, center.y, 0.0, 0.0],
+                };
+                (
+                    gradient.end_color.as_linear_rgba_f32(),
+                    [
+                        gradient.stops.0,
+                        gradient.stops.1,
+                        gradient.range.0,
+                        gradient.range.1,
+                    ],
+                    shape,
+                )
+            }
+            None => (color, [0.0; 4], [0.0; 4]),
+        };
 
         match extracted_sprite.quad_type {
-            UIQuadType::Quad => extracted_sprite.type_index = quad_type_offset,
+            UIQuadType::Quad => {
+                extracted_sprite.type_index =
+                    match extracted_sprite.gradient.map(|gradient| gradient.shape) {
+                        Some(GradientShape::Linear { .. }) => linear_gradient_type_offset,
+                        Some(GradientShape::Radial { .. }) => radial_gradient_type_offset,
+                        None => quad_type_offset,
+                    }
+            }
             UIQuadType::Text => extracted_sprite.type_index = text_type_offset,
             UIQuadType::Image => extracted_sprite.type_index = image_type_offset,
+            UIQuadType::BoxShadow if box_shadow.inset => {
+                extracted_sprite.type_index = inset_box_shadow_type_offset
+            }
+            UIQuadType::BoxShadow => extracted_sprite.type_index = box_shadow_type_offset,
             UIQuadType::Clip => {}
         };
 
@@ -446,11 +573,18 @@ pub fn prepare_quads(
         extracted_sprite.vertex_index = i;
         for (index, vertex_position) in QUAD_VERTEX_POSITIONS.iter().enumerate() {
             let world = Mat4::from_scale_rotation_translation(
-                sprite_rect.size().extend(1.0),
+                quad_rect.size().extend(1.0),
                 Quat::default(),
-                sprite_rect.min.extend(0.0),
+                quad_rect.min.extend(0.0),
             );
-            let final_position = (world * Vec3::from(*vertex_position).extend(1.0)).truncate();
+            let local_position = (world * Vec3::from(*vertex_position).extend(1.0)).truncate();
+            let final_position = match extracted_sprite.transform {
+                Some(transform) => {
+                    let (x, y) = transform.transform_point((local_position.x, local_position.y));
+                    Vec3::new(x, y, local_position.z)
+                }
+                None => local_position,
+            };
             sprite_meta.vertices.push(QuadVertex {
                 position: final_position.into(),
                 color,
@@ -461,6 +595,18 @@ pub fn prepare_quads(
                     sprite_rect.size().x,
                     sprite_rect.size().y,
                 ],
+                border: [border_top, border_right, border_bottom, border_left],
+                border_color,
+                shadow: [
+                    box_shadow.offset.0,
+                    box_shadow.offset.1,
+                    box_shadow.blur,
+                    box_shadow.spread,
+                ],
+                gradient_color,
+                gradient_stops,
+                gradient_shape,
+                local_position: [local_position.x, local_position.y],
             });
         }
     }
@@ -1,35 +1,151 @@
-use bevy::{math::Vec2, sprite::Rect};
-use kayak_core::render_primitive::RenderPrimitive;
+use bevy::{math::Vec2, render::color::Color, sprite::Rect};
+use kayak_core::{render_primitive::RenderPrimitive, Background, BoxShadow};
 
 use crate::{
-    render::unified::pipeline::{ExtractQuadBundle, ExtractedQuad, UIQuadType},
+    render::unified::pipeline::{
+        ExtractQuadBundle, ExtractedQuad, GradientSegment, GradientShape, UIQuadType,
+    },
     to_bevy_color,
 };
 
 pub fn extract_quads(render_primitive: &RenderPrimitive, dpi: f32) -> Vec<ExtractQuadBundle> {
-    let (background_color, layout, border_radius) = match render_primitive {
+    let (background, layout, border_radius, border, border_color) = match render_primitive {
         RenderPrimitive::Quad {
-            background_color,
+            background,
             layout,
             border_radius,
-        } => (background_color, layout, border_radius),
+            border,
+            border_color,
+        } => (background, layout, border_radius, border, border_color),
         _ => panic!(""),
     };
 
+    let quad = ExtractedQuad {
+        rect: Rect {
+            min: Vec2::new(layout.posx, layout.posy),
+            max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height) * dpi,
+        },
+        color: Color::NONE,
+        vertex_index: 0,
+        char_id: 0,
+        z_index: layout.z_index,
+        font_handle: None,
+        quad_type: UIQuadType::Quad,
+        type_index: 0,
+        border_radius: *border_radius,
+        border: (
+            border.0 * dpi,
+            border.1 * dpi,
+            border.2 * dpi,
+            border.3 * dpi,
+        ),
+        border_color: to_bevy_color(border_color),
+        box_shadow: None,
+        gradient: None,
+        transform: None,
+        image: None,
+        uv_max: None,
+        uv_min: None,
+    };
+
+    let shape = match background {
+        Background::Color(color) => {
+            return vec![ExtractQuadBundle {
+                extracted_quad: ExtractedQuad {
+                    color: to_bevy_color(color),
+                    ..quad
+                },
+            }]
+        }
+        Background::LinearGradient { angle, .. } => GradientShape::Linear {
+            angle: angle.to_radians(),
+        },
+        Background::RadialGradient { center, .. } => GradientShape::Radial {
+            center: Vec2::new(center.0, center.1),
+        },
+    };
+
+    // A gradient without stops is transparent, and one with a single stop is filled with its color
+    let stops = background.stops();
+    if stops.len() < 2 {
+        let color = stops
+            .first()
+            .map_or(Color::NONE, |stop| to_bevy_color(&stop.color));
+        return vec![ExtractQuadBundle {
+            extracted_quad: ExtractedQuad { color, ..quad },
+        }];
+    }
+
+    let last_segment = stops.len() - 2;
+    stops
+        .windows(2)
+        .enumerate()
+        .filter_map(|(index, pair)| {
+            let (start, end) = (&pair[0], &pair[1]);
+            let range = (
+                if index == 0 { f32::MIN } else { start.position },
+                if index == last_segment {
+                    f32::MAX
+                } else {
+                    end.position
+                },
+            );
+            // Stops at the same position are a hard transition, leaving nothing to draw between them
+            if range.0 >= range.1 {
+                return None;
+            }
+
+            Some(ExtractQuadBundle {
+                extracted_quad: ExtractedQuad {
+                    color: to_bevy_color(&start.color),
+                    gradient: Some(GradientSegment {
+                        shape,
+                        stops: (start.position, end.position),
+                        end_color: to_bevy_color(&end.color),
+                        range,
+                    }),
+                    ..quad.clone()
+                },
+            })
+        })
+        .collect()
+}
+
+pub fn extract_box_shadows(render_primitive: &RenderPrimitive, dpi: f32) -> Vec<ExtractQuadBundle> {
+    let (layout, border_radius, box_shadow) = match render_primitive {
+        RenderPrimitive::BoxShadow {
+            layout,
+            border_radius,
+            box_shadow,
+        } => (layout, border_radius, box_shadow),
+        _ => panic!(""),
+    };
+
+    // The rect is the one of the quad casting the shadow, the area the shadow covers is computed when preparing it
     vec![ExtractQuadBundle {
         extracted_quad: ExtractedQuad {
             rect: Rect {
                 min: Vec2::new(layout.posx, layout.posy),
                 max: Vec2::new(layout.posx + layout.width, layout.posy + layout.height) * dpi,
             },
-            color: to_bevy_color(background_color),
+            color: to_bevy_color(&box_shadow.color),
             vertex_index: 0,
             char_id: 0,
             z_index: layout.z_index,
             font_handle: None,
-            quad_type: UIQuadType::Quad,
+            quad_type: UIQuadType::BoxShadow,
             type_index: 0,
             border_radius: *border_radius,
+            border: (0.0, 0.0, 0.0, 0.0),
+            border_color: Color::NONE,
+            box_shadow: Some(BoxShadow {
+                offset: (box_shadow.offset.0 * dpi, box_shadow.offset.1 * dpi),
+                blur: box_shadow.blur * dpi,
+                spread: box_shadow.spread * dpi,
+                ..*box_shadow
+            }),
+            gradient: None,
+            transform: None,
             image: None,
             uv_max: None,
             uv_min: None,
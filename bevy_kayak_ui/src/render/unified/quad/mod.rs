@@ -1,2 +1,2 @@
 mod extract;
-pub use extract::extract_quads;
+pub use extract::{extract_box_shadows, extract_quads};
@@ -19,6 +19,14 @@ struct VertexOutput {
     [[location(3)]] size: vec2<f32>;
     [[location(4)]] screen_position: vec2<f32>;
     [[location(5)]] border_radius: f32;
+    [[location(6)]] border: vec4<f32>;
+    [[location(7)]] border_color: vec4<f32>;
+    [[location(8)]] shadow: vec4<f32>;
+    [[location(9)]] gradient_color: vec4<f32>;
+    [[location(10)]] gradient_stops: vec4<f32>;
+    [[location(11)]] gradient_shape: vec4<f32>;
+    // The position of the fragment before the transform of the quad, if any
+    [[location(12)]] local_position: vec2<f32>;
 };
 
 [[stage(vertex)]]
@@ -27,6 +35,13 @@ fn vertex(
     [[location(1)]] vertex_color: vec4<f32>,
     [[location(2)]] vertex_uv: vec4<f32>,
     [[location(3)]] vertex_pos_size: vec4<f32>,
+    [[location(4)]] vertex_border: vec4<f32>,
+    [[location(5)]] vertex_border_color: vec4<f32>,
+    [[location(6)]] vertex_shadow: vec4<f32>,
+    [[location(7)]] vertex_gradient_color: vec4<f32>,
+    [[location(8)]] vertex_gradient_stops: vec4<f32>,
+    [[location(9)]] vertex_gradient_shape: vec4<f32>,
+    [[location(10)]] vertex_local_position: vec2<f32>,
 ) -> VertexOutput {
     var out: VertexOutput;
     out.color = vertex_color;
@@ -36,6 +51,13 @@ fn vertex(
     out.uv = vertex_uv.xyz;
     out.size = vertex_pos_size.zw;
     out.border_radius = vertex_uv.w;
+    out.border = vertex_border;
+    out.border_color = vertex_border_color;
+    out.shadow = vertex_shadow;
+    out.gradient_color = vertex_gradient_color;
+    out.gradient_stops = vertex_gradient_stops;
+    out.gradient_shape = vertex_gradient_shape;
+    out.local_position = vertex_local_position;
     return out;
 }
 
@@ -69,11 +91,93 @@ fn sd_box_rounded(
     return length(dist);
 }
 
+// Signed distance to the edge of a rounded box, negative inside of it
+fn sd_box_rounded_signed(
+    frag_coord: vec2<f32>,
+    position: vec2<f32>,
+    size: vec2<f32>,
+    radius: f32,
+) -> f32 {
+    var half_size = max(size * 0.5, vec2<f32>(0.0));
+    var r = clamp(radius, 0.0, min(half_size.x, half_size.y));
+    var q = abs(frag_coord - (position + half_size)) - half_size + r;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - r;
+}
+
+// How much of a box shadow covers the fragment, given the quad casting it
+// The shadow is a rounded box grown by the spread (x, y: offset, z: blur, w: spread)
+fn box_shadow(
+    frag_coord: vec2<f32>,
+    position: vec2<f32>,
+    size: vec2<f32>,
+    radius: f32,
+    shadow: vec4<f32>,
+    inset: bool,
+) -> f32 {
+    var spread = shadow.w;
+    if (inset) {
+        spread = -spread;
+    }
+    var blur = max(shadow.z, 0.5);
+    var dist = sd_box_rounded_signed(
+        frag_coord,
+        position + shadow.xy - spread,
+        size + spread * 2.0,
+        max(radius + spread, 0.0),
+    );
+    var shadow_alpha = 1.0 - smoothStep(-blur, blur, dist);
+
+    var quad_dist = sd_box_rounded(frag_coord, position, size, radius);
+    var quad_alpha = 1.0 - smoothStep(max(radius - 0.5, 0.0), radius + 0.5, quad_dist);
+
+    // Drop shadows are only visible around the quad, and inset shadows inside of it
+    if (inset) {
+        return quad_alpha * (1.0 - shadow_alpha);
+    }
+    return shadow_alpha * (1.0 - quad_alpha);
+}
+
+// Where the fragment lies along a gradient, 0.0 being its start and 1.0 its end
+// Linear gradients go through the center of the quad at an angle (shape.x, in radians, clockwise from the top)
+// and radial gradients go from their center (shape.xy, relative to the size of the quad) to the farthest corner
+fn gradient_position(
+    frag_coord: vec2<f32>,
+    position: vec2<f32>,
+    size: vec2<f32>,
+    shape: vec4<f32>,
+    radial: bool,
+) -> f32 {
+    if (radial) {
+        var center = position + size * shape.xy;
+        var farthest = max(abs(center - position), abs(position + size - center));
+        return length(frag_coord - center) / max(length(farthest), 0.0001);
+    }
+
+    var direction = vec2<f32>(sin(shape.x), -cos(shape.x));
+    var gradient_length = abs(size.x * direction.x) + abs(size.y * direction.y);
+    var from_center = frag_coord - (position + size * 0.5);
+    return dot(from_center, direction) / max(gradient_length, 0.0001) + 0.5;
+}
+
 [[stage(fragment)]]
 fn fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    if (quad_type.t == 0) {
+    if (quad_type.t == 0 || quad_type.t == 5 || quad_type.t == 6) {
+        var fill = in.color;
+        if (quad_type.t != 0) {
+            // Each segment of the gradient is its own quad, which only draws the fragments within the segment
+            var g = gradient_position(in.local_position, in.pos, in.size, in.gradient_shape, quad_type.t == 6);
+            if (g < in.gradient_stops.z || g >= in.gradient_stops.w) {
+                discard;
+            }
+            var t = clamp(
+                (g - in.gradient_stops.x) / max(in.gradient_stops.y - in.gradient_stops.x, 0.0001),
+                0.0,
+                1.0);
+            fill = mix(in.color, in.gradient_color, t);
+        }
+
         var dist = sd_box_rounded(
-            in.position.xy,
+            in.local_position,
             in.pos,
             in.size,
             in.border_radius,
@@ -83,7 +187,34 @@ fn fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
             in.border_radius + 0.5,
             dist);
 
-        return vec4<f32>(in.color.rgb, dist);
+        // The border is drawn inside the quad: top, right, bottom, left
+        var border_width = max(max(in.border.x, in.border.y), max(in.border.z, in.border.w));
+        if (border_width <= 0.0) {
+            return vec4<f32>(fill.rgb, fill.a * dist);
+        }
+
+        var inner_radius = max(in.border_radius - border_width, 0.0);
+        var inner_dist = sd_box_rounded(
+            in.local_position,
+            in.pos + in.border.wx,
+            in.size - in.border.wx - in.border.yz,
+            inner_radius,
+        );
+        inner_dist = 1.0 - smoothStep(
+            max(inner_radius - 0.5, 0.0),
+            inner_radius + 0.5,
+            inner_dist);
+
+        var color = mix(in.border_color, fill, inner_dist);
+        return vec4<f32>(color.rgb, color.a * dist);
+    }
+    if (quad_type.t == 3) {
+        var alpha = box_shadow(in.local_position, in.pos, in.size, in.border_radius, in.shadow, false);
+        return vec4<f32>(in.color.rgb, in.color.a * alpha);
+    }
+    if (quad_type.t == 4) {
+        var alpha = box_shadow(in.local_position, in.pos, in.size, in.border_radius, in.shadow, true);
+        return vec4<f32>(in.color.rgb, in.color.a * alpha);
     }
     if (quad_type.t == 1) {
         var px_range = 3.5;
@@ -94,7 +225,7 @@ fn fragment(in: VertexOutput) -> [[location(0)]] vec4<f32> {
         var sig_dist = (v - 0.5) * dot(msdf_unit, 0.5 / fwidth(in.uv.xy));
         var a = clamp(sig_dist + 0.5, 0.0, 1.0);
 
-        return vec4<f32>(in.color.rgb, a);
+        return vec4<f32>(in.color.rgb, a * in.color.a);
     }
     if (quad_type.t == 2) {
         var color = textureSample(image_texture, image_sampler, vec2<f32>(in.uv.x, 1.0 - in.uv.y));
@@ -0,0 +1,87 @@
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::{AssetEvent, Assets, EventReader, Handle, Local, Res},
+    reflect::TypeUuid,
+};
+use kayak_core::{parse_stylesheet, StyleSheet};
+
+use crate::BevyContext;
+
+/// A stylesheet loaded from a `.kss` file (see [parse_stylesheet])
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "1b3c9a3e-5f0e-4d6b-9a8f-2f8d6f1c7e42"]
+pub struct StyleSheetAsset(pub StyleSheet);
+
+#[derive(Default)]
+pub struct StyleSheetLoader;
+
+impl AssetLoader for StyleSheetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let source = std::str::from_utf8(bytes)?;
+            let stylesheet = parse_stylesheet(source)?;
+            load_context.set_default_asset(LoadedAsset::new(StyleSheetAsset(stylesheet)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["kss"];
+        EXTENSIONS
+    }
+}
+
+/// The stylesheet applied to the UI, whose classes are added to the ones the UI already has (e.g., the ones of
+/// the built-in widgets)
+///
+/// The stylesheet is applied again whenever the file changes when asset hot reloading is enabled:
+///
+/// ```ignore
+/// fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+///     asset_server.watch_for_changes().unwrap();
+///     commands.insert_resource(UiStyleSheet(asset_server.load("ui.kss")));
+/// }
+/// ```
+pub struct UiStyleSheet(pub Handle<StyleSheetAsset>);
+
+/// Applies the [UiStyleSheet] once it's loaded and every time it's modified
+///
+/// The widgets using a class that changed are styled and laid out again on the next update, without restarting.
+pub fn update_stylesheet(
+    bevy_context: Res<BevyContext>,
+    ui_stylesheet: Option<Res<UiStyleSheet>>,
+    stylesheets: Res<Assets<StyleSheetAsset>>,
+    mut stylesheet_events: EventReader<AssetEvent<StyleSheetAsset>>,
+    mut base_stylesheet: Local<Option<StyleSheet>>,
+) {
+    let ui_stylesheet = match ui_stylesheet {
+        Some(ui_stylesheet) => ui_stylesheet,
+        None => return,
+    };
+
+    let has_changed = stylesheet_events.iter().any(|event| match event {
+        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+            *handle == ui_stylesheet.0
+        }
+        AssetEvent::Removed { .. } => false,
+    });
+    if !has_changed {
+        return;
+    }
+
+    if let (Some(stylesheet), Ok(mut context)) = (
+        stylesheets.get(&ui_stylesheet.0),
+        bevy_context.kayak_context.write(),
+    ) {
+        // Classes removed from the file go back to the stylesheet the UI had before it was loaded
+        let mut combined = base_stylesheet
+            .get_or_insert_with(|| context.stylesheet().clone())
+            .clone();
+        combined.extend(stylesheet.0.clone());
+        context.set_stylesheet(combined);
+    }
+}
@@ -102,6 +102,19 @@ fn EffectCounter() {
         println!("Second!");
     }, []);
 
+    // A side-effect can also return a cleanup closure. It's called right before the side-effect runs again
+    // and when the widget is removed from the tree, making it the perfect place to undo whatever the
+    // side-effect set up (unsubscribing from events, stopping timers, etc.).
+    use_effect!(
+        || {
+            println!("Mounted!");
+            || {
+                println!("Unmounted!");
+            }
+        },
+        []
+    );
+
     rsx! {
         <Window position={(50.0, 225.0)} size={(300.0, 150.0)} title={"Effect Example".to_string()}>
             <Text size={16.0} content={format!("Actual Count: {}", count)} />
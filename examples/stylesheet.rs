@@ -0,0 +1,58 @@
+use bevy::{
+    prelude::{App as BevyApp, AssetServer, Commands, Res, ResMut},
+    window::WindowDescriptor,
+    DefaultPlugins,
+};
+use kayak_ui::bevy::{BevyContext, BevyKayakUIPlugin, FontMapping, UICameraBundle, UiStyleSheet};
+use kayak_ui::core::{render, rsx, widget, Index};
+use kayak_ui::widgets::{App, Button, Text, Window};
+
+#[widget]
+fn StyledWidgets() {
+    rsx! {
+        <Window position={(50.0, 50.0)} size={(300.0, 300.0)} title={"Stylesheet Example".to_string()}>
+            <Text class={Some("label".to_string())} size={24.0} content={"Styled from a file".to_string()}>{}</Text>
+            <Button>
+                <Text class={Some("label".to_string())} size={24.0} content={"Hover me".to_string()}>{}</Text>
+            </Button>
+        </Window>
+    }
+}
+
+fn startup(
+    mut commands: Commands,
+    mut font_mapping: ResMut<FontMapping>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn_bundle(UICameraBundle::new());
+
+    font_mapping.add(asset_server.load("roboto.kayak_font"));
+
+    // Reloads `styles.kss` whenever it's saved
+    asset_server.watch_for_changes().unwrap();
+    commands.insert_resource(UiStyleSheet(asset_server.load("styles.kss")));
+
+    let context = BevyContext::new(|context| {
+        render! {
+            <App>
+                <StyledWidgets />
+            </App>
+        }
+    });
+
+    commands.insert_resource(context);
+}
+
+fn main() {
+    BevyApp::new()
+        .insert_resource(WindowDescriptor {
+            width: 1270.0,
+            height: 720.0,
+            title: String::from("UI Example"),
+            ..Default::default()
+        })
+        .add_plugins(DefaultPlugins)
+        .add_plugin(BevyKayakUIPlugin)
+        .add_startup_system(startup)
+        .run();
+}
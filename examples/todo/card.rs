@@ -7,7 +7,7 @@ use kayak_ui::widgets::{Background, Text};
 
 use super::delete_button::DeleteButton;
 
-#[widget]
+#[widget(no_memo)]
 pub fn Card(card_id: usize, name: String, on_delete: Handler<usize>) {
     let background_styles = Style {
         layout_type: StyleProp::Value(LayoutType::Row),
@@ -3,7 +3,7 @@ use kayak_ui::widgets::Element;
 
 use super::{card::Card, Todo};
 
-#[widget]
+#[widget(no_memo)]
 pub fn Cards(cards: Vec<Todo>, on_delete: Handler<usize>) {
     rsx! {
         <Element>
@@ -11,9 +11,8 @@ pub fn Cards(cards: Vec<Todo>, on_delete: Handler<usize>) {
                 cards
                     .clone()
                     .into_iter()
-                    .enumerate()
-                    .map(|(index, todo)| constructor! { <Card card_id={index} name={todo.name.clone()} on_delete={on_delete.clone()} /> }),
-            )}
+                    .map(|todo| constructor! { <Card card_id={todo.id} name={todo.name.clone()} on_delete={on_delete.clone()} /> }),
+            ).with_key(|card: &Card| card.card_id.to_string())}
         </Element>
     }
 }
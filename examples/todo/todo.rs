@@ -20,6 +20,7 @@ use cards::Cards;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Todo {
+    id: usize,
     name: String,
 }
 
@@ -27,12 +28,15 @@ pub struct Todo {
 fn TodoApp() {
     let (todos, set_todos, ..) = use_state!(vec![
         Todo {
+            id: 0,
             name: "Use bevy to make a game!".to_string(),
         },
         Todo {
+            id: 1,
             name: "Help contribute to bevy!".to_string(),
         },
         Todo {
+            id: 2,
             name: "Join the bevy discord!".to_string(),
         },
     ]);
@@ -63,7 +67,13 @@ fn TodoApp() {
     let add_events = OnEvent::new(move |_, event| match event.event_type {
         EventType::Click => {
             if !new_todo_value_cloned.is_empty() {
+                let id = todos_cloned
+                    .iter()
+                    .map(|todo| todo.id + 1)
+                    .max()
+                    .unwrap_or(0);
                 todos_cloned.push(Todo {
+                    id,
                     name: new_todo_value_cloned.clone(),
                 });
                 cloned_set_todos(todos_cloned.clone());
@@ -75,7 +85,7 @@ fn TodoApp() {
     let mut todos_cloned = todos.clone();
     let cloned_set_todos = set_todos.clone();
     let handle_delete = Handler::new(move |card_id: usize| {
-        todos_cloned.remove(card_id);
+        todos_cloned.retain(|todo| todo.id != card_id);
         cloned_set_todos(todos_cloned.clone());
     });
 
@@ -24,7 +24,7 @@ fn startup(
                     constructor! {
                         <Text content={data.clone().to_string()} size={16.0} />
                     }
-                }))}
+                })).with_key(|text: &Text| text.content.clone())}
             </App>
         }
     });
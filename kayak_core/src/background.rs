@@ -0,0 +1,190 @@
+use crate::{color::Color, layout_cache::Rect};
+
+/// A color at a position along a gradient
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    pub color: Color,
+    /// Where the color is along the gradient, from 0.0 at its start to 1.0 at its end
+    pub position: f32,
+}
+
+impl ColorStop {
+    pub fn new(color: Color, position: f32) -> Self {
+        Self { color, position }
+    }
+}
+
+/// What fills a quad, set with the `background` style
+///
+/// Gradients blend between their color stops, which must be sorted by position. Before the first stop and after
+/// the last one, the gradient is the color of that stop.
+///
+/// ```ignore
+/// let health_bar = Background::LinearGradient {
+///     angle: 90.0,
+///     stops: vec![
+///         ColorStop::new(Color::new(0.8, 0.1, 0.1, 1.0), 0.0),
+///         ColorStop::new(Color::new(0.1, 0.8, 0.1, 1.0), 1.0),
+///     ],
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    Color(Color),
+    /// A gradient along a line going through the center of the quad
+    ///
+    /// Like in CSS, the angle is in degrees, clockwise from the top: `0.0` goes from the bottom to the top and
+    /// `90.0` from the left to the right. The line is long enough for the corners of the quad to be exactly the
+    /// colors at its start and end.
+    LinearGradient {
+        angle: f32,
+        stops: Vec<ColorStop>,
+    },
+    /// A circular gradient starting at `center` and ending at the farthest corner of the quad
+    ///
+    /// The center is relative to the size of the quad: `(0.5, 0.5)` is the middle of the quad.
+    RadialGradient {
+        center: (f32, f32),
+        stops: Vec<ColorStop>,
+    },
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self::Color(Color::TRANSPARENT)
+    }
+}
+
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl Background {
+    /// Returns the color stops of a gradient, which are empty for a single color
+    pub fn stops(&self) -> &[ColorStop] {
+        match self {
+            Background::Color(_) => &[],
+            Background::LinearGradient { stops, .. } | Background::RadialGradient { stops, .. } => {
+                stops
+            }
+        }
+    }
+
+    /// Returns where a point of a quad with the given layout lies along the gradient, 0.0 being its start and 1.0
+    /// its end
+    ///
+    /// The GPU renderer computes the same position per fragment.
+    pub fn gradient_position(&self, layout: &Rect, point: (f32, f32)) -> f32 {
+        match self {
+            Background::Color(_) => 0.0,
+            Background::LinearGradient { angle, .. } => {
+                let (sin, cos) = angle.to_radians().sin_cos();
+                let length = (layout.width * sin).abs() + (layout.height * cos).abs();
+                if length <= 0.0 {
+                    return 0.0;
+                }
+                let center = (
+                    layout.posx + layout.width / 2.0,
+                    layout.posy + layout.height / 2.0,
+                );
+                // The y axis points down, so going up means going towards negative y
+                let projected = (point.0 - center.0) * sin - (point.1 - center.1) * cos;
+                projected / length + 0.5
+            }
+            Background::RadialGradient { center, .. } => {
+                let center = (
+                    layout.posx + layout.width * center.0,
+                    layout.posy + layout.height * center.1,
+                );
+                let farthest_x = (center.0 - layout.posx)
+                    .abs()
+                    .max((layout.posx + layout.width - center.0).abs());
+                let farthest_y = (center.1 - layout.posy)
+                    .abs()
+                    .max((layout.posy + layout.height - center.1).abs());
+                let radius = farthest_x.hypot(farthest_y);
+                if radius <= 0.0 {
+                    return 0.0;
+                }
+                (point.0 - center.0).hypot(point.1 - center.1) / radius
+            }
+        }
+    }
+
+    /// Returns the color of the background at a point of a quad with the given layout
+    pub fn color_at(&self, layout: &Rect, point: (f32, f32)) -> Color {
+        match self {
+            Background::Color(color) => *color,
+            _ => sample_stops(self.stops(), self.gradient_position(layout, point)),
+        }
+    }
+}
+
+/// Returns the color at a position along the color stops
+fn sample_stops(stops: &[ColorStop], position: f32) -> Color {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Color::TRANSPARENT,
+    };
+    if position < first.position {
+        return first.color;
+    }
+
+    for pair in stops.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        if position < end.position {
+            let t = ((position - start.position) / (end.position - start.position)).clamp(0.0, 1.0);
+            let mix = |start: f32, end: f32| start + (end - start) * t;
+            return Color::new(
+                mix(start.color.r, end.color.r),
+                mix(start.color.g, end.color.g),
+                mix(start.color.b, end.color.b),
+                mix(start.color.a, end.color.a),
+            );
+        }
+    }
+    last.color
+}
+
+#[test]
+fn test_gradient_colors() {
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+    let layout = Rect {
+        posx: 0.0,
+        posy: 0.0,
+        width: 100.0,
+        height: 50.0,
+        z_index: 0.0,
+    };
+    let stops = vec![ColorStop::new(red, 0.0), ColorStop::new(blue, 1.0)];
+
+    let left_to_right = Background::LinearGradient {
+        angle: 90.0,
+        stops: stops.clone(),
+    };
+    assert_eq!(left_to_right.color_at(&layout, (0.0, 25.0)), red);
+    assert_eq!(
+        left_to_right.color_at(&layout, (50.0, 0.0)),
+        Color::new(0.5, 0.0, 0.5, 1.0)
+    );
+    assert_eq!(left_to_right.color_at(&layout, (100.0, 25.0)), blue);
+
+    let bottom_to_top = Background::LinearGradient {
+        angle: 0.0,
+        stops: stops.clone(),
+    };
+    assert_eq!(bottom_to_top.color_at(&layout, (50.0, 50.0)), red);
+    assert_eq!(bottom_to_top.color_at(&layout, (50.0, 0.0)), blue);
+
+    let radial = Background::RadialGradient {
+        center: (0.0, 0.0),
+        stops,
+    };
+    assert_eq!(radial.color_at(&layout, (0.0, 0.0)), red);
+    assert_eq!(radial.color_at(&layout, (100.0, 50.0)), blue);
+
+    assert_eq!(Background::Color(red).color_at(&layout, (10.0, 10.0)), red);
+}
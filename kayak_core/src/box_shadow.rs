@@ -0,0 +1,56 @@
+use crate::{color::Color, layout_cache::Rect};
+
+/// A shadow drawn around or inside a quad, like the CSS `box-shadow` property
+///
+/// The shadow follows the rounded corners of the quad. A glow is a shadow without offset in a bright color:
+///
+/// ```ignore
+/// let glow = BoxShadow {
+///     blur: 12.0,
+///     spread: 2.0,
+///     color: Color::new(0.3, 0.6, 1.0, 0.8),
+///     ..BoxShadow::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxShadow {
+    /// How far the shadow is moved to the right and to the bottom, in pixels
+    pub offset: (f32, f32),
+    /// The distance over which the edge of the shadow fades out, on each side of the edge, in pixels
+    pub blur: f32,
+    /// How much the shadow grows (or shrinks if negative) on each side before being blurred, in pixels
+    pub spread: f32,
+    pub color: Color,
+    /// Draws the shadow inside the quad, over its background, instead of behind it
+    pub inset: bool,
+}
+
+impl Default for BoxShadow {
+    fn default() -> Self {
+        Self {
+            offset: (0.0, 0.0),
+            blur: 0.0,
+            spread: 0.0,
+            color: Color::TRANSPARENT,
+            inset: false,
+        }
+    }
+}
+
+impl BoxShadow {
+    /// Returns the area the shadow of a quad with the given layout can cover
+    pub fn bounds(&self, layout: &Rect) -> Rect {
+        if self.inset {
+            return *layout;
+        }
+
+        let extent = self.spread.max(0.0) + self.blur.max(0.0);
+        Rect {
+            posx: layout.posx + self.offset.0 - extent,
+            posy: layout.posy + self.offset.1 - extent,
+            width: layout.width + extent * 2.0,
+            height: layout.height + extent * 2.0,
+            z_index: layout.z_index,
+        }
+    }
+}
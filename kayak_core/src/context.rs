@@ -1,7 +1,10 @@
 use crate::{Binding, Changeable};
+use std::any::Any;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use crate::{multi_state::MultiState, widget_manager::WidgetManager, Index, InputEvent, MutableBound, Releasable};
+use crate::effect::{Cleanup, Effect, EffectCleanup};
 use crate::event_dispatcher::EventDispatcher;
 
 pub struct KayakContext {
@@ -14,10 +17,22 @@ pub struct KayakContext {
     global_bindings: HashMap<crate::Index, Vec<flo_binding::Uuid>>,
     widget_state_lifetimes:
     HashMap<crate::Index, HashMap<flo_binding::Uuid, Box<dyn crate::Releasable>>>,
+    /// Callbacks registered with [on_unmount](Self::on_unmount), run when their widget is removed from the tree
+    widget_unmount_callbacks: HashMap<crate::Index, Vec<Box<dyn FnOnce() + Send + Sync>>>,
     current_id: Index,
     // TODO: Make widget_manager private.
     pub widget_manager: WidgetManager,
     event_dispatcher: EventDispatcher,
+    /// Whether events are being dispatched, in which case `event_dispatcher` is a stale copy
+    is_processing_events: bool,
+    /// Focus requested with [set_focus](Self::set_focus) while events were being dispatched, where `Some(None)`
+    /// clears the focus
+    focus_request: Option<Option<Index>>,
+    /// Events sent with [dispatch_custom_event](Self::dispatch_custom_event) that haven't been dispatched yet
+    custom_events: Vec<Event>,
+    /// The widget that captured the pointer with [capture_pointer](Self::capture_pointer)
+    pointer_capture: Option<Index>,
+    event_config: EventConfig,
     global_state: resources::Resources,
     last_state_type_id: Option<std::any::TypeId>,
     current_state_index: usize,
@@ -41,9 +56,15 @@ impl KayakContext {
             widget_providers: HashMap::new(),
             global_bindings: HashMap::new(),
             widget_state_lifetimes: HashMap::new(),
+            widget_unmount_callbacks: HashMap::new(),
             current_id: crate::Index::default(),
             widget_manager: WidgetManager::new(),
             event_dispatcher: EventDispatcher::default(),
+            is_processing_events: false,
+            focus_request: None,
+            custom_events: Vec::new(),
+            pointer_capture: None,
+            event_config: EventConfig::default(),
             global_state: resources::Resources::default(),
             last_state_type_id: None,
             current_state_index: 0,
@@ -129,9 +150,11 @@ impl KayakContext {
     /// Creates a context consumer for the given type, [T]
     ///
     /// This allows direct access to a parent's state data made with [create_provider](Self::create_provider).
+    /// The current widget will re-render whenever the provider's state changes.
     pub fn create_consumer<T: resources::Resource + Clone + PartialEq>(&mut self) -> Option<Binding<T>> {
         let type_id = std::any::TypeId::of::<T>();
 
+        let mut consumed_state = None;
         if let Some(providers) = self.widget_providers.get(&type_id) {
             let mut index = Some(self.current_id);
             while index.is_some() {
@@ -141,13 +164,24 @@ impl KayakContext {
                 let key = index.unwrap();
                 if let Some(provider) = providers.get(&key) {
                     if let Ok(state) = provider.get::<Binding<T>>() {
-                        return Some(state.clone());
+                        consumed_state = Some(state.clone());
+                        break;
                     }
                 }
             }
         }
 
-        None
+        if let Some(state) = consumed_state.as_ref() {
+            let lifetime = Self::create_lifetime(state, &self.widget_manager, self.current_id);
+            Self::insert_state_lifetime(
+                &mut self.widget_state_lifetimes,
+                self.current_id,
+                state.id,
+                lifetime,
+            );
+        }
+
+        consumed_state
     }
 
     pub fn set_current_id(&mut self, id: crate::Index) {
@@ -155,6 +189,74 @@ impl KayakContext {
         self.current_state_index = 0;
         self.current_effect_index = 0;
         self.last_state_type_id = None;
+        // Unmount callbacks are registered again on every render
+        self.widget_unmount_callbacks.remove(&id);
+    }
+
+    /// Registers a callback that runs when the current widget is unmounted
+    ///
+    /// A widget is unmounted when it's removed from the tree, such as when an `If` condition becomes
+    /// false or a `VecTracker` shrinks. Callbacks are cleared every time the widget renders, so only
+    /// the ones registered during its last render will run.
+    pub fn on_unmount<F: FnOnce() + Send + Sync + 'static>(&mut self, callback: F) {
+        self.widget_unmount_callbacks
+            .entry(self.current_id)
+            .or_insert(Vec::default())
+            .push(Box::new(callback));
+    }
+
+    /// Unmounts the given widgets, releasing all of their state, effects, providers and bindings
+    ///
+    /// This is meant to be called with the nodes returned by [Tree::merge](crate::Tree::merge) after
+    /// a widget has rendered its children.
+    pub fn unmount(&mut self, widgets: Vec<Index>) {
+        for id in widgets {
+            if let Some(callbacks) = self.widget_unmount_callbacks.remove(&id) {
+                for callback in callbacks {
+                    callback();
+                }
+            }
+
+            self.widget_states.remove(&id);
+
+            if let Some(mut effects) = self.widget_effects.remove(&id) {
+                if let Some(effects) = effects.remove::<MultiState<Effect>>() {
+                    for mut effect in effects.data {
+                        effect.release();
+                    }
+                }
+            }
+
+            for providers in self.widget_providers.values_mut() {
+                providers.remove(&id);
+            }
+
+            self.global_bindings.remove(&id);
+            if let Some(lifetimes) = self.widget_state_lifetimes.remove(&id) {
+                for (_, mut lifetime) in lifetimes {
+                    lifetime.done();
+                }
+            }
+
+            if self.pointer_capture == Some(id) {
+                self.pointer_capture = None;
+            }
+            self.event_dispatcher.remove_node(id);
+            self.widget_manager.remove(id);
+        }
+    }
+
+    /// Returns whether any state, effect, provider, binding or unmount callback is still held for the given widget
+    pub(crate) fn has_widget_data(&self, id: Index) -> bool {
+        self.widget_states.contains_key(&id)
+            || self.widget_effects.contains_key(&id)
+            || self
+                .widget_providers
+                .values()
+                .any(|providers| providers.contains_key(&id))
+            || self.global_bindings.contains_key(&id)
+            || self.widget_state_lifetimes.contains_key(&id)
+            || self.widget_unmount_callbacks.contains_key(&id)
     }
 
     pub fn create_state<T: resources::Resource + Clone + PartialEq>(
@@ -220,6 +322,9 @@ impl KayakContext {
     /// All dependencies must be implement the [Changeable](crate::Changeable) trait, which means it will generally
     /// work best with [Binding](crate::Binding) values.
     ///
+    /// The effect may return a cleanup closure (see [EffectCleanup](crate::EffectCleanup)). This closure is called
+    /// right before the effect runs again and when the widget that created the effect is unmounted.
+    ///
     /// For more details, check out [React's documentation](https://reactjs.org/docs/hooks-effect.html),
     /// upon which this method is based.
     ///
@@ -241,10 +346,37 @@ impl KayakContext {
     /// context.create_effect(move || {
     ///     println!("Value: {}", my_state_clone.get());
     /// }, &[&my_state]);
+    ///
+    /// let my_state_clone = my_state.clone();
+    /// context.create_effect(move || {
+    ///     println!("Subscribed at: {}", my_state_clone.get());
+    ///     || println!("Unsubscribed!")
+    /// }, &[&my_state]);
     /// ```
-    pub fn create_effect<'a, F: Fn() + Send + Sync + 'static>(&'a mut self, effect: F, dependencies: &[&'a dyn Changeable]) {
+    pub fn create_effect<'a, F, R>(&'a mut self, effect: F, dependencies: &[&'a dyn Changeable])
+    where
+        F: Fn() -> R + Send + Sync + 'static,
+        R: EffectCleanup,
+    {
         // === Bind to Dependencies === //
-        let notification = crate::notify(effect);
+        let cleanup: Arc<Mutex<Option<Cleanup>>> = Arc::new(Mutex::new(None));
+        let effect_cleanup = cleanup.clone();
+        let notification = crate::notify(move || {
+            // Take the previous cleanup out first so the lock isn't held while running user code
+            let previous_cleanup = if let Ok(mut cleanup) = effect_cleanup.lock() {
+                cleanup.take()
+            } else {
+                None
+            };
+            if let Some(previous_cleanup) = previous_cleanup {
+                previous_cleanup();
+            }
+
+            let next_cleanup = effect().into_cleanup();
+            if let Ok(mut cleanup) = effect_cleanup.lock() {
+                *cleanup = next_cleanup;
+            }
+        });
         let mut lifetimes = Vec::default();
         for dependency in dependencies {
             let lifetime = dependency.when_changed(notification.clone());
@@ -262,17 +394,18 @@ impl KayakContext {
         };
 
         // === Insert Effect === //
+        let effect = Effect { lifetimes, cleanup };
         let effects = self.widget_effects.entry(self.current_id).or_insert(resources::Resources::default());
-        if effects.contains::<MultiState<Vec<Box<dyn Releasable>>>>() {
-            let mut state = effects.get_mut::<MultiState<Vec<Box<dyn Releasable>>>>().unwrap();
+        if effects.contains::<MultiState<Effect>>() {
+            let mut state = effects.get_mut::<MultiState<Effect>>().unwrap();
             let old_size = state.data.len();
-            state.get_or_add(lifetimes, &mut self.current_effect_index);
+            state.get_or_add(effect, &mut self.current_effect_index);
             if old_size != state.data.len() {
                 // Just added -> invoke effect
                 invoke_effect();
             }
         } else {
-            let state = MultiState::new(lifetimes);
+            let state = MultiState::new(effect);
             effects.insert(state);
             invoke_effect();
             self.current_effect_index += 1;
@@ -361,6 +494,10 @@ impl KayakContext {
                 panic!("Couldn't get lock on dirty nodes!")
             };
         for node_index in dirty_nodes {
+            if !self.widget_manager.current_widgets.contains(node_index) {
+                // Widget was unmounted while rendering one of its ancestors
+                continue;
+            }
             let mut widget = self.widget_manager.take(node_index);
             widget.render(self);
             self.widget_manager.repossess(widget);
@@ -378,10 +515,10 @@ impl KayakContext {
     /// associated [W3 specifications](https://www.w3.org/TR/uievents/#dom-event-architecture).
     ///
     /// ## Capture:
-    /// Currently, we do not support the Capture Phase. This is because the current event handling system is
-    ///   made to handle events as a single enum. To achieve proper capturing, widgets would need to be able to
-    ///   register separate event handlers so that specific ones could be captured while others would not. It
-    ///   should generally be okay to skip this as it's not a common use-case.
+    ///   The Capture Phase travels down the tree from the root to the target's parent, giving ancestors a chance
+    ///   to handle an event before its target does (e.g., a modal intercepting clicks). Only handlers created
+    ///   with [`OnEvent::with_capture`](crate::OnEvent::with_capture) are called during this phase. Calling
+    ///   [`event.stop_propagation()`](Event::stop_propagation) here prevents the event from reaching the target.
     ///
     /// ## Target:
     ///   The Target Phase simply identifies the target for an event so that we can generate the propagation path
@@ -392,10 +529,159 @@ impl KayakContext {
     ///   firing the bubbled event along the way. At any point, the bubbling can be stopped by calling
     ///   [`event.stop_propagation()`](Event::stop_propagation). Not every event can be propagated, in which case,
     ///   they will only fire for their specified target.
+    ///
+    /// This should be called every frame, even without input events, so that events depending on timing (e.g.,
+    /// [LongPress](crate::EventType::LongPress)) can fire.
     pub fn process_events(&mut self, input_events: Vec<InputEvent>) {
+        self.process_events_at(input_events, Instant::now());
+    }
+
+    /// Processes the given input events as if they occurred at `time`
+    ///
+    /// This is useful to control timing in tests and replays. See [process_events](Self::process_events).
+    pub fn process_events_at(&mut self, input_events: Vec<InputEvent>, time: Instant) {
         let mut dispatcher = self.event_dispatcher.to_owned();
-        dispatcher.process_events(input_events, self);
+        self.is_processing_events = true;
+        dispatcher.process_events(input_events, time, self);
+        self.dispatch_pending_events(&mut dispatcher);
+        self.is_processing_events = false;
         self.event_dispatcher = dispatcher;
+        self.widget_manager
+            .set_interaction(self.event_dispatcher.interaction());
+    }
+
+    /// Moves the focus to the given widget, or clears it when given `None`, firing [Blur](crate::EventType::Blur)
+    /// on the currently focused widget and [Focus](crate::EventType::Focus) on the new one
+    ///
+    /// The widget doesn't need to be [focusable](crate::Widget::focusable), which allows focusing widgets that
+    /// can't be clicked or tabbed to. Widgets that aren't mounted are ignored. When called from an event handler,
+    /// focus changes once every event has been dispatched.
+    pub fn set_focus(&mut self, index: Option<Index>) {
+        if let Some(index) = index {
+            if !self.widget_manager.current_widgets.contains(index) {
+                return;
+            }
+        }
+
+        if self.is_processing_events {
+            self.focus_request = Some(index);
+            return;
+        }
+
+        let mut dispatcher = self.event_dispatcher.to_owned();
+        self.is_processing_events = true;
+        self.focus_request = Some(index);
+        self.dispatch_pending_events(&mut dispatcher);
+        self.is_processing_events = false;
+        self.event_dispatcher = dispatcher;
+        self.widget_manager
+            .set_interaction(self.event_dispatcher.interaction());
+    }
+
+    /// Sends an event of any type to the given widget, which then bubbles up through its ancestors
+    ///
+    /// Handlers receive it as [EventType::Custom] and can read it with [Event::custom]. Like any other event,
+    /// it can be captured and its propagation can be stopped. The event is dispatched after the events currently
+    /// being processed, or during the next call to [process_events](Self::process_events) otherwise. It's dropped
+    /// if the target is unmounted in the meantime.
+    ///
+    /// ```ignore
+    /// struct ItemSelected(u32);
+    ///
+    /// context.dispatch_custom_event(self.get_id(), ItemSelected(3));
+    /// ```
+    pub fn dispatch_custom_event<T: Any + Send + Sync>(&mut self, target: Index, event: T) {
+        self.custom_events.push(Event::new(
+            target,
+            EventType::Custom(CustomEvent::new(event)),
+        ));
+    }
+
+    /// Applies the focus requested with [set_focus](Self::set_focus) and dispatches the pending custom events,
+    /// including the ones requested by the handlers of those events
+    fn dispatch_pending_events(&mut self, dispatcher: &mut EventDispatcher) {
+        loop {
+            if let Some(index) = self.focus_request.take() {
+                dispatcher.set_focus(index, self);
+                continue;
+            }
+
+            let mut custom_events = std::mem::take(&mut self.custom_events);
+            if custom_events.is_empty() {
+                break;
+            }
+            // The target might have been unmounted since the event was queued
+            custom_events
+                .retain(|event| self.widget_manager.current_widgets.contains(event.target));
+            dispatcher.dispatch_untracked_events(custom_events, self);
+        }
+    }
+
+    /// Makes the given widget the target of every mouse event until [release_pointer](Self::release_pointer) is
+    /// called, regardless of where the mouse is
+    ///
+    /// This lets widgets keep track of the mouse once it leaves their bounds, like a slider thumb being dragged
+    /// past the end of its track. The widget needs a layout to receive events. The capture is released
+    /// automatically when the widget is unmounted.
+    pub fn capture_pointer(&mut self, index: Index) {
+        self.pointer_capture = Some(index);
+    }
+
+    /// Releases the pointer captured with [capture_pointer](Self::capture_pointer)
+    pub fn release_pointer(&mut self) {
+        self.pointer_capture = None;
+    }
+
+    /// Gets the widget that captured the pointer, if any
+    pub fn pointer_capture(&self) -> Option<Index> {
+        self.pointer_capture
+    }
+
+    /// Sets the thresholds used to synthesize events like [DoubleClick](crate::EventType::DoubleClick)
+    pub fn set_event_config(&mut self, config: EventConfig) {
+        self.event_config = config;
+    }
+
+    pub fn event_config(&self) -> EventConfig {
+        self.event_config
+    }
+
+    /// Gets the cursor icon of the topmost widget under the mouse (or the widget that captured the pointer), as of
+    /// the last call to [process_events](Self::process_events)
+    ///
+    /// Renderers should show this icon as the OS cursor.
+    pub fn cursor_icon(&self) -> crate::CursorIcon {
+        self.event_dispatcher.cursor_icon()
+    }
+
+    /// Sets the stylesheet the classes of widgets are looked up in
+    ///
+    /// The styles of the widgets using a class that changed are rebuilt and laid out on the next render, without
+    /// rendering the widgets themselves. This makes it cheap to reload a stylesheet (see
+    /// [parse_stylesheet](crate::parse_stylesheet)) while the UI is running.
+    pub fn set_stylesheet(&mut self, stylesheet: StyleSheet) {
+        self.widget_manager.set_stylesheet(stylesheet);
+    }
+
+    pub fn stylesheet(&self) -> &StyleSheet {
+        &self.widget_manager.stylesheet
+    }
+
+    /// Sets the stylesheet providing the default styles of classes
+    ///
+    /// The classes of the [stylesheet](Self::set_stylesheet) are applied on top of the default ones with the same
+    /// name, so it only needs to set the properties it changes. The built-in widgets use this to style themselves.
+    pub fn set_default_stylesheet(&mut self, stylesheet: StyleSheet) {
+        self.widget_manager.set_default_stylesheet(stylesheet);
+    }
+
+    pub fn default_stylesheet(&self) -> &StyleSheet {
+        &self.widget_manager.default_stylesheet
+    }
+
+    /// Gets the currently focused widget
+    pub fn current_focus(&self) -> Option<Index> {
+        self.event_dispatcher.current_focus()
     }
 
     #[allow(dead_code)]
@@ -16,3 +16,36 @@ impl Default for PointerEvents {
         Self::All
     }
 }
+
+/// The icon of the mouse cursor while it is over a node
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CursorIcon {
+    /// The platform's default cursor (usually an arrow)
+    Default,
+    /// A hand, for clickable content
+    Pointer,
+    /// An I-beam, for editable or selectable text
+    Text,
+    /// An open hand, for content that can be dragged
+    Grab,
+    /// A closed hand, for content being dragged
+    Grabbing,
+    /// A four-way arrow, for content that can be moved
+    Move,
+    /// A left-right arrow
+    ResizeHorizontal,
+    /// An up-down arrow
+    ResizeVertical,
+    /// A diagonal arrow from the top left to the bottom right
+    ResizeNwse,
+    /// A diagonal arrow from the top right to the bottom left
+    ResizeNesw,
+    /// For content that can't be interacted with
+    NotAllowed,
+}
+
+impl Default for CursorIcon {
+    fn default() -> Self {
+        Self::Default
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::{Arc, Mutex};
+
+use crate::Releasable;
+
+/// A boxed closure that tears down a side-effect
+pub type Cleanup = Box<dyn FnOnce() + Send + Sync + 'static>;
+
+/// The value returned by a side-effect created with [create_effect](crate::KayakContext::create_effect)
+///
+/// Effects can either return nothing (`()`), or a closure that cleans up after them. This cleanup closure
+/// is called right before the effect runs again and when the owning widget is unmounted.
+pub trait EffectCleanup {
+    fn into_cleanup(self) -> Option<Cleanup>;
+}
+
+impl EffectCleanup for () {
+    fn into_cleanup(self) -> Option<Cleanup> {
+        None
+    }
+}
+
+impl<F: FnOnce() + Send + Sync + 'static> EffectCleanup for F {
+    fn into_cleanup(self) -> Option<Cleanup> {
+        Some(Box::new(self))
+    }
+}
+
+impl<F: FnOnce() + Send + Sync + 'static> EffectCleanup for Option<F> {
+    fn into_cleanup(self) -> Option<Cleanup> {
+        self.map(|cleanup| Box::new(cleanup) as Cleanup)
+    }
+}
+
+/// The stored data of a single side-effect
+pub(crate) struct Effect {
+    /// The lifetimes of the dependency bindings
+    pub lifetimes: Vec<Box<dyn Releasable>>,
+    /// The cleanup returned by the last invocation of the effect
+    pub cleanup: Arc<Mutex<Option<Cleanup>>>,
+}
+
+impl Effect {
+    /// Releases the dependency bindings and runs the pending cleanup, if any
+    pub fn release(&mut self) {
+        for lifetime in self.lifetimes.iter_mut() {
+            lifetime.done();
+        }
+
+        let cleanup = if let Ok(mut cleanup) = self.cleanup.lock() {
+            cleanup.take()
+        } else {
+            None
+        };
+
+        if let Some(cleanup) = cleanup {
+            cleanup();
+        }
+    }
+}
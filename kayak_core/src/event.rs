@@ -1,6 +1,10 @@
+use std::any::Any;
+use std::sync::Arc;
+use std::time::Duration;
+
 use crate::{Index, KeyCode};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Event {
     /// The node targeted by this event
     pub target: Index,
@@ -8,8 +12,29 @@ pub struct Event {
     pub current_target: Index,
     /// The type of event
     pub event_type: EventType,
+    /// The modifier keys that were held down when this event was fired
+    pub modifiers: Modifiers,
+    /// The phase of the dispatch this event is in
+    pub phase: EventPhase,
     /// Indicates whether this event should propagate or not
     pub(crate) should_propagate: bool,
+    /// Indicates whether [stop_propagation](Self::stop_propagation) was called
+    pub(crate) propagation_stopped: bool,
+    /// The payload set with [set_drag_payload](Self::set_drag_payload)
+    pub(crate) drag_payload: DragPayload,
+}
+
+/// The phases an event goes through when dispatched
+///
+/// These are based on the [W3 specifications](https://www.w3.org/TR/uievents/#dom-event-architecture).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventPhase {
+    /// The event travels from the root down to the target's parent
+    Capture,
+    /// The event has reached its target
+    Target,
+    /// The event travels from the target's parent back up to the root
+    Bubble,
 }
 
 impl Default for Event {
@@ -18,7 +43,11 @@ impl Default for Event {
             target: Default::default(),
             current_target: Default::default(),
             event_type: EventType::Click,
+            modifiers: Modifiers::default(),
+            phase: EventPhase::Target,
             should_propagate: true,
+            propagation_stopped: false,
+            drag_payload: DragPayload::default(),
         }
     }
 }
@@ -32,23 +61,175 @@ impl Event {
         Self {
             target,
             current_target: target,
-            event_type,
             should_propagate: event_type.propagates(),
+            event_type,
+            modifiers: Modifiers::default(),
+            phase: EventPhase::Target,
+            propagation_stopped: false,
+            drag_payload: DragPayload::default(),
         }
     }
 
+    /// Returns the value of this event if it is a [custom event](EventType::Custom) of type `T`
+    pub fn custom<T: Any>(&self) -> Option<&T> {
+        match &self.event_type {
+            EventType::Custom(custom) => custom.get::<T>(),
+            _ => None,
+        }
+    }
+
+    /// Sets the payload carried by the drag that this [DragStart](EventType::DragStart) event starts
+    ///
+    /// The payload is delivered to the widget the drag is dropped on with [Drop](EventType::Drop). This has no
+    /// effect on other events.
+    pub fn set_drag_payload<T: Any + Send + Sync>(&mut self, payload: T) {
+        self.drag_payload = DragPayload::new(payload);
+    }
+
     /// Returns whether this event is currently set to propagate
     pub fn propagates(&self) -> bool {
         self.should_propagate
     }
 
-    /// If called, prevents this event from propagating up the hierarchy
+    /// If called, prevents this event from propagating any further
+    ///
+    /// During the capture phase, this also prevents the event from reaching its target.
     pub fn stop_propagation(&mut self) {
         self.should_propagate = false;
+        self.propagation_stopped = true;
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// An event of a type defined outside of this crate
+///
+/// See [KayakContext::dispatch_custom_event](crate::KayakContext::dispatch_custom_event). Custom events are compared
+/// by identity, so two of them are only equal if they are clones of each other.
+#[derive(Clone)]
+pub struct CustomEvent {
+    value: Arc<dyn Any + Send + Sync>,
+    type_name: &'static str,
+}
+
+impl CustomEvent {
+    pub fn new<T: Any + Send + Sync>(value: T) -> Self {
+        Self {
+            value: Arc::new(value),
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+
+    /// Returns the value if it is of type `T`
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        self.value.is::<T>()
+    }
+}
+
+impl PartialEq for CustomEvent {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.value, &other.value)
+    }
+}
+
+impl std::fmt::Debug for CustomEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CustomEvent").field(&self.type_name).finish()
+    }
+}
+
+/// Thresholds used to synthesize events from mouse input
+///
+/// Set it with [KayakContext::set_event_config](crate::KayakContext::set_event_config).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EventConfig {
+    /// The maximum time between two clicks for them to count as a [DoubleClick](EventType::DoubleClick)
+    pub double_click_interval: Duration,
+    /// The maximum distance in pixels between two clicks for them to count as a [DoubleClick](EventType::DoubleClick)
+    pub double_click_distance: f32,
+    /// How long the mouse needs to be held down before [LongPress](EventType::LongPress) fires
+    pub long_press_duration: Duration,
+    /// How long the mouse needs to stay over a widget before [HoverStart](EventType::HoverStart) fires
+    pub hover_delay: Duration,
+    /// The distance in pixels the mouse needs to move while pressed before [DragStart](EventType::DragStart) fires
+    pub drag_threshold: f32,
+}
+
+impl Default for EventConfig {
+    fn default() -> Self {
+        Self {
+            double_click_interval: Duration::from_millis(500),
+            double_click_distance: 4.0,
+            long_press_duration: Duration::from_millis(500),
+            hover_delay: Duration::from_millis(500),
+            drag_threshold: 5.0,
+        }
+    }
+}
+
+/// A value carried from the source of a drag to the widget it is dropped on
+///
+/// Payloads are compared by identity, so two payloads are only equal if they are clones of each other.
+#[derive(Clone, Default)]
+pub struct DragPayload(Option<Arc<dyn Any + Send + Sync>>);
+
+impl DragPayload {
+    pub fn new<T: Any + Send + Sync>(value: T) -> Self {
+        Self(Some(Arc::new(value)))
+    }
+
+    /// Returns the payload if it is of type `T`
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.0.as_ref().and_then(|value| value.downcast_ref::<T>())
+    }
+
+    /// Returns whether the drag source didn't set a payload
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl PartialEq for DragPayload {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DragPayload")
+            .field(&if self.is_empty() { "empty" } else { "..." })
+            .finish()
+    }
+}
+
+/// The state of the modifier keys
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    /// The Windows key, or Command on Mac
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    /// Returns whether any modifier key is held down
+    pub fn any(&self) -> bool {
+        self.shift || self.control || self.alt || self.super_key
+    }
+}
+
+/// The type of an event
+///
+/// `MouseDown`, `MouseUp` and `Click` refer to the left mouse button, the other buttons have their own variants.
+#[derive(Debug, Clone, PartialEq)]
 pub enum EventType {
     Click,
     Hover,
@@ -56,17 +237,96 @@ pub enum EventType {
     MouseOut,
     MouseDown,
     MouseUp,
+    RightClick,
+    RightMouseDown,
+    RightMouseUp,
+    MiddleClick,
+    MiddleMouseDown,
+    MiddleMouseUp,
+    /// The mouse wheel was scrolled over the target, in pixels
+    Scroll {
+        delta_x: f32,
+        delta_y: f32,
+    },
     Focus,
     Blur,
-    CharInput { c: char },
-    KeyboardInput { key: KeyCode },
+    CharInput {
+        c: char,
+    },
+    /// A key was pressed, or released when `is_pressed` is `false`, while the target was focused
+    ///
+    /// Handlers that only care about presses should match `is_pressed: true`.
+    KeyboardInput {
+        key: KeyCode,
+        is_pressed: bool,
+    },
+    /// The back action was used while the target was focused (e.g., to close a menu)
+    Back,
+    /// The mouse moved [far enough](EventConfig::drag_threshold) while pressed over the target to start dragging it
+    DragStart,
+    /// The dragged target moved, by `delta` pixels since the last drag event
+    Drag {
+        delta: (f32, f32),
+    },
+    /// The drag of the target ended, whether or not it was dropped on a widget
+    DragEnd,
+    /// A drag entered the target
+    DragEnter,
+    /// A drag left the target
+    DragLeave,
+    /// A drag was dropped on the target
+    Drop {
+        payload: DragPayload,
+    },
+    /// The target was clicked twice in [quick succession](EventConfig::double_click_interval)
+    DoubleClick,
+    /// The mouse was held down on the target for [a while](EventConfig::long_press_duration)
+    LongPress,
+    /// The mouse stayed over the target for [a while](EventConfig::hover_delay)
+    HoverStart,
+    /// A finger touched the target
+    ///
+    /// The other touch events of this finger, identified by `id`, target the same widget even once the finger
+    /// moved outside of it.
+    TouchStart {
+        id: u64,
+        position: (f32, f32),
+    },
+    TouchMove {
+        id: u64,
+        position: (f32, f32),
+    },
+    TouchEnd {
+        id: u64,
+        position: (f32, f32),
+    },
+    TouchCancel {
+        id: u64,
+    },
+    /// An event sent with [KayakContext::dispatch_custom_event](crate::KayakContext::dispatch_custom_event)
+    Custom(CustomEvent),
+}
+
+// Some variants hold floats or a payload, so these can't be derived. The dispatcher drops scroll inputs with a NaN
+// delta, so that the event types it keys its state on are always equal to themselves. Only the variant is hashed,
+// which keeps hashing consistent with equality.
+impl Eq for EventType {}
+
+impl std::hash::Hash for EventType {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EventCategory {
     Mouse,
     Keyboard,
+    Touch,
     Focus,
+    Navigation,
+    Drag,
+    Custom,
 }
 
 impl EventType {
@@ -81,8 +341,30 @@ impl EventType {
             Self::Click => true,
             Self::MouseDown => true,
             Self::MouseUp => true,
+            Self::RightClick => true,
+            Self::RightMouseDown => true,
+            Self::RightMouseUp => true,
+            Self::MiddleClick => true,
+            Self::MiddleMouseDown => true,
+            Self::MiddleMouseUp => true,
+            Self::Scroll { .. } => true,
             Self::CharInput { .. } => true,
             Self::KeyboardInput { .. } => true,
+            Self::Back => true,
+            Self::DragStart => true,
+            Self::Drag { .. } => true,
+            Self::DragEnd => true,
+            Self::DragEnter => true,
+            Self::DragLeave => true,
+            Self::Drop { .. } => true,
+            Self::DoubleClick => true,
+            Self::LongPress => true,
+            Self::HoverStart => true,
+            Self::TouchStart { .. } => true,
+            Self::TouchMove { .. } => true,
+            Self::TouchEnd { .. } => true,
+            Self::TouchCancel { .. } => true,
+            Self::Custom(..) => true,
             // Doesn't Propagate
             Self::MouseIn => false,
             Self::MouseOut => false,
@@ -99,14 +381,40 @@ impl EventType {
             Self::Click => EventCategory::Mouse,
             Self::MouseDown => EventCategory::Mouse,
             Self::MouseUp => EventCategory::Mouse,
+            Self::RightClick => EventCategory::Mouse,
+            Self::RightMouseDown => EventCategory::Mouse,
+            Self::RightMouseUp => EventCategory::Mouse,
+            Self::MiddleClick => EventCategory::Mouse,
+            Self::MiddleMouseDown => EventCategory::Mouse,
+            Self::MiddleMouseUp => EventCategory::Mouse,
+            Self::Scroll { .. } => EventCategory::Mouse,
+            Self::DoubleClick => EventCategory::Mouse,
+            Self::LongPress => EventCategory::Mouse,
+            Self::HoverStart => EventCategory::Mouse,
             Self::MouseIn => EventCategory::Mouse,
             Self::MouseOut => EventCategory::Mouse,
+            // Touch
+            Self::TouchStart { .. } => EventCategory::Touch,
+            Self::TouchMove { .. } => EventCategory::Touch,
+            Self::TouchEnd { .. } => EventCategory::Touch,
+            Self::TouchCancel { .. } => EventCategory::Touch,
             // Keyboard
             Self::CharInput { .. } => EventCategory::Keyboard,
             Self::KeyboardInput { .. } => EventCategory::Keyboard,
             // Focus
             Self::Focus => EventCategory::Focus,
             Self::Blur => EventCategory::Focus,
+            // Navigation
+            Self::Back => EventCategory::Navigation,
+            // Drag
+            Self::DragStart => EventCategory::Drag,
+            Self::Drag { .. } => EventCategory::Drag,
+            Self::DragEnd => EventCategory::Drag,
+            Self::DragEnter => EventCategory::Drag,
+            Self::DragLeave => EventCategory::Drag,
+            Self::Drop { .. } => EventCategory::Drag,
+            // Custom
+            Self::Custom(..) => EventCategory::Custom,
         }
     }
 }
@@ -1,6 +1,6 @@
-use std::collections::{HashMap, HashSet};
-use crate::{Event, EventType, Index, InputEvent, InputEventCategory, KayakContext, PointerEvents};
 use crate::layout_cache::Rect;
+use crate::styles::StyleProp;
+use crate::stylesheet::Interaction;
 use crate::widget_manager::WidgetManager;
 
 type EventMap = HashMap<Index, HashSet<EventType>>;
@@ -28,12 +28,70 @@ impl Default for EventState {
     }
 }
 
+/// A drag in progress, or one that may start once the mouse moves far enough
+#[derive(Debug, Clone)]
+struct DragState {
+    source: Index,
+    /// Where the mouse was pressed
+    origin: (f32, f32),
+    /// The mouse position of the last drag event
+    last_position: (f32, f32),
+    /// Whether the threshold was crossed and [DragStart](EventType::DragStart) was fired
+    is_dragging: bool,
+    /// The widget the drag is currently over
+    hovered: Option<Index>,
+    payload: DragPayload,
+}
+
+/// The last click, used to detect double clicks
+#[derive(Debug, Clone)]
+struct ClickState {
+    node: Index,
+    time: Instant,
+    position: (f32, f32),
+}
+
+/// The widget the mouse is held down on, used to detect long presses
+#[derive(Debug, Clone)]
+struct PressState {
+    node: Index,
+    pressed_at: Instant,
+    is_long_press: bool,
+}
+
+/// The widget the mouse is over, used to delay hover events
+#[derive(Debug, Clone)]
+struct HoverState {
+    node: Index,
+    entered_at: Instant,
+    has_started: bool,
+}
+
+/// A finger currently touching the screen
+#[derive(Debug, Clone)]
+struct TouchState {
+    /// The widget the touch started on, which receives all of its events
+    target: Option<Index>,
+    position: (f32, f32),
+}
+
 #[derive(Debug, Default, Clone)]
 pub(crate) struct EventDispatcher {
     is_mouse_pressed: bool,
     current_focus: Option<Index>,
     current_mouse_position: (f32, f32),
     next_mouse_position: (f32, f32),
+    modifiers: Modifiers,
+    drag: Option<DragState>,
+    last_click: Option<ClickState>,
+    press: Option<PressState>,
+    hover: Option<HoverState>,
+    touches: HashMap<u64, TouchState>,
+    /// The topmost widget under the mouse
+    hovered: Option<Index>,
+    /// The topmost widget the mouse was pressed on, until it's released
+    active: Option<Index>,
+    cursor_icon: CursorIcon,
     previous_events: EventMap,
 }
 
@@ -45,7 +103,6 @@ impl EventDispatcher {
     }
 
     /// Gets the currently focused widget
-    #[allow(dead_code)]
     pub fn current_focus(&self) -> Option<Index> {
         self.current_focus
     }
@@ -56,17 +113,115 @@ impl EventDispatcher {
         self.current_mouse_position
     }
 
+    /// Gets the cursor icon of the widget under the mouse
+    pub fn cursor_icon(&self) -> CursorIcon {
+        self.cursor_icon
+    }
+
+    /// Gets the widgets the user is interacting with, used to style them
+    pub fn interaction(&self) -> Interaction {
+        let mut touched: Vec<Index> = self
+            .touches
+            .values()
+            .filter_map(|touch| touch.target)
+            .collect();
+        touched.sort();
+        touched.dedup();
+        Interaction {
+            hovered: self.hovered,
+            active: self.active,
+            touched,
+            focused: self.current_focus,
+        }
+    }
+
+    /// Gets the modifier keys that are currently held down
+    #[allow(dead_code)]
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Removes every reference to the given widget (used when a widget is unmounted)
+    pub fn remove_node(&mut self, index: Index) {
+        self.previous_events.remove(&index);
+        if self.current_focus == Some(index) {
+            self.current_focus = None;
+        }
+        if let Some(drag) = &mut self.drag {
+            if drag.source == index {
+                self.drag = None;
+            } else if drag.hovered == Some(index) {
+                drag.hovered = None;
+            }
+        }
+        if self.last_click.as_ref().map(|click| click.node) == Some(index) {
+            self.last_click = None;
+        }
+        if self.press.as_ref().map(|press| press.node) == Some(index) {
+            self.press = None;
+        }
+        if self.hover.as_ref().map(|hover| hover.node) == Some(index) {
+            self.hover = None;
+        }
+        if self.hovered == Some(index) {
+            self.hovered = None;
+        }
+        if self.active == Some(index) {
+            self.active = None;
+        }
+        for touch in self.touches.values_mut() {
+            if touch.target == Some(index) {
+                touch.target = None;
+            }
+        }
+    }
+
     /// Process and dispatch an [InputEvent](crate::InputEvent)
     #[allow(dead_code)]
-    pub fn process_event(&mut self, input_event: InputEvent, context: &mut KayakContext) {
-        let events = self.build_event_stream(&[input_event], &context.widget_manager);
-        self.dispatch_events(events, context);
+    pub fn process_event(
+        &mut self,
+        input_event: InputEvent,
+        time: Instant,
+        context: &mut KayakContext,
+    ) {
+        self.process_events(vec![input_event], time, context);
     }
 
     /// Process and dispatch a set of [InputEvents](crate::InputEvent)
-    pub fn process_events(&mut self, input_events: Vec<InputEvent>, context: &mut KayakContext) {
-        let events = self.build_event_stream(&input_events, &context.widget_manager);
+    ///
+    /// `time` is the time the events occurred at, which is used for the events that depend on timing (e.g.,
+    /// [LongPress](EventType::LongPress)).
+    pub fn process_events(
+        &mut self,
+        input_events: Vec<InputEvent>,
+        time: Instant,
+        context: &mut KayakContext,
+    ) {
+        let events = self.build_event_stream(
+            &input_events,
+            &context.widget_manager,
+            context.pointer_capture(),
+            &context.event_config(),
+            time,
+        );
+        self.dispatch_events(events, context);
+    }
+
+    /// Moves focus to the given widget (or clears it), firing the corresponding [Focus](EventType::Focus) and
+    /// [Blur](EventType::Blur) events
+    pub fn set_focus(&mut self, index: Option<Index>, context: &mut KayakContext) {
+        let events = self.change_focus(index);
+        self.dispatch_untracked_events(events, context);
+    }
+
+    /// Dispatches events that don't come from input, like custom events
+    ///
+    /// Unlike [dispatch_events](Self::dispatch_events), these don't affect the events tracked between input
+    /// updates (e.g., a pending click).
+    pub fn dispatch_untracked_events(&mut self, events: Vec<Event>, context: &mut KayakContext) {
+        let previous_events = self.previous_events.clone();
         self.dispatch_events(events, context);
+        self.previous_events = previous_events;
     }
 
     /// Dispatch an [Event](crate::Event)
@@ -80,27 +235,45 @@ impl EventDispatcher {
         // === Dispatch Events === //
         let mut next_events = HashMap::default();
         for event in events {
+            // --- Capture Event --- //
+            // Every event is captured from the root down to the target's parent, even if it doesn't bubble
+            let mut ancestors = Vec::new();
+            let mut current_parent = context.widget_manager.node_tree.get_parent(event.target);
+            while let Some(parent) = current_parent {
+                ancestors.push(parent);
+                current_parent = context.widget_manager.node_tree.get_parent(parent);
+            }
+
+            let was_captured = ancestors.into_iter().rev().any(|index| {
+                let node_event =
+                    Self::call_event(context, event.clone(), index, EventPhase::Capture);
+                node_event.propagation_stopped
+            });
+            if was_captured {
+                continue;
+            }
+
             let mut current_target: Option<Index> = Some(event.target);
             while let Some(index) = current_target {
-                // Create a copy of the event, specific for this node
-                // This is to make sure unauthorized changes to the event are not propagated
-                // (e.g., changing the event type, removing the target, etc.)
-                let mut node_event = Event {
-                    current_target: index,
-                    ..event
-                };
-
                 // --- Update State --- //
-                Self::insert_event(
-                    &mut next_events,
-                    &index,
-                    node_event.event_type,
-                );
+                Self::insert_event(&mut next_events, &index, event.event_type.clone());
 
                 // --- Call Event --- //
-                let mut target_widget = context.widget_manager.take(index);
-                target_widget.on_event(context, &mut node_event);
-                context.widget_manager.repossess(target_widget);
+                let phase = if index == event.target {
+                    EventPhase::Target
+                } else {
+                    EventPhase::Bubble
+                };
+                let node_event = Self::call_event(context, event.clone(), index, phase);
+
+                // --- Drag Payload --- //
+                if node_event.event_type == EventType::DragStart
+                    && !node_event.drag_payload.is_empty()
+                {
+                    if let Some(drag) = &mut self.drag {
+                        drag.payload = node_event.drag_payload.clone();
+                    }
+                }
 
                 // --- Propagate Event --- //
                 if node_event.should_propagate {
@@ -132,8 +305,41 @@ impl EventDispatcher {
         self.previous_events = next_events;
     }
 
+    /// Calls the event handler of the given node, returning the event after it was handled
+    fn call_event(
+        context: &mut KayakContext,
+        event: Event,
+        index: Index,
+        phase: EventPhase,
+    ) -> Event {
+        // Create a copy of the event, specific for this node
+        // This is to make sure unauthorized changes to the event are not propagated
+        // (e.g., changing the event type, removing the target, etc.)
+        let mut node_event = Event {
+            current_target: index,
+            phase,
+            ..event
+        };
+
+        let mut target_widget = context.widget_manager.take(index);
+        target_widget.on_event(context, &mut node_event);
+        context.widget_manager.repossess(target_widget);
+
+        node_event
+    }
+
     /// Generates a stream of [Events](crate::Event) from a set of [InputEvents](crate::InputEvent)
-    fn build_event_stream(&mut self, input_events: &[InputEvent], widget_manager: &WidgetManager) -> Vec<Event> {
+    ///
+    /// While a widget has [captured the pointer](KayakContext::capture_pointer), it is the target of every mouse
+    /// event instead of the widgets under the mouse.
+    fn build_event_stream(
+        &mut self,
+        input_events: &[InputEvent],
+        widget_manager: &WidgetManager,
+        pointer_capture: Option<Index>,
+        config: &EventConfig,
+        time: Instant,
+    ) -> Vec<Event> {
         let mut event_stream = Vec::<Event>::new();
         let mut states: HashMap<EventType, EventState> = HashMap::new();
 
@@ -144,7 +350,29 @@ impl EventDispatcher {
         };
 
         // === Mouse Events === //
-        let mut stack: Vec<TreeNode> = vec![(root, 0)];
+        let pointer_capture =
+            pointer_capture.filter(|index| widget_manager.current_widgets.contains(*index));
+        if let Some(captured) = pointer_capture {
+            for input_event in input_events {
+                if matches!(input_event.category(), InputEventCategory::Mouse) {
+                    let events = self.process_pointer_events(
+                        input_event,
+                        (captured, 0),
+                        &mut states,
+                        widget_manager,
+                        true,
+                    );
+                    event_stream.extend(events);
+                }
+            }
+        }
+
+        // The tree only needs to be hit-tested when the pointer isn't captured
+        let mut stack: Vec<TreeNode> = if pointer_capture.is_some() {
+            Vec::new()
+        } else {
+            vec![(root, 0)]
+        };
         while stack.len() > 0 {
             let (current, depth) = stack.pop().unwrap();
             let mut enter_children = true;
@@ -153,16 +381,17 @@ impl EventDispatcher {
                 // --- Process Event --- //
                 if matches!(input_event.category(), InputEventCategory::Mouse) {
                     // A widget's PointerEvents style will determine how it and its children are processed
-                    let mut pointer_events = PointerEvents::default();
-                    if let Some(widget) = widget_manager.current_widgets.get(current).unwrap() {
-                        if let Some(styles) = widget.get_styles() {
-                            pointer_events = styles.pointer_events.resolve();
-                        }
-                    }
+                    let pointer_events = Self::pointer_events(widget_manager, current);
 
                     match pointer_events {
                         PointerEvents::All | PointerEvents::SelfOnly => {
-                            let events = self.process_pointer_events(input_event, (current, depth), &mut states, widget_manager);
+                            let events = self.process_pointer_events(
+                                input_event,
+                                (current, depth),
+                                &mut states,
+                                widget_manager,
+                                false,
+                            );
                             event_stream.extend(events);
 
                             if matches!(pointer_events, PointerEvents::SelfOnly) {
@@ -187,18 +416,95 @@ impl EventDispatcher {
 
         // === Keyboard Events === //
         for input_event in input_events {
+            if let InputEvent::Keyboard { key, is_pressed } = input_event {
+                self.update_modifiers(*key, *is_pressed);
+            }
+
             // Keyboard events only care about the currently focused widget so we don't need to run this over every node in the tree
             let events = self.process_keyboard_events(input_event, &mut states, widget_manager);
             event_stream.extend(events);
+
+            if let InputEvent::Keyboard {
+                key: KeyCode::Tab,
+                is_pressed: true,
+            } = input_event
+            {
+                let next_focus = self.next_tab_focus(widget_manager, self.modifiers.shift);
+                if next_focus.is_some() {
+                    let events = self.change_focus(next_focus);
+                    event_stream.extend(events);
+                }
+            }
         }
 
+        // === Touch Events === //
+        for input_event in input_events {
+            if matches!(input_event.category(), InputEventCategory::Touch) {
+                let events =
+                    self.process_touch_events(input_event, widget_manager, pointer_capture);
+                event_stream.extend(events);
+            }
+        }
+
+        // === Navigation Events === //
+        for input_event in input_events {
+            let events = self.process_navigation_events(input_event, widget_manager);
+            event_stream.extend(events);
+        }
+
+        // === Drag Events === //
+        let events = self.process_drag_events(input_events, &mut states, config);
+        event_stream.extend(events);
+
+        let has_mouse_moved = input_events
+            .iter()
+            .any(|input_event| matches!(input_event, InputEvent::MouseMoved(..)));
+        let hovered = states
+            .get(&EventType::Hover)
+            .and_then(|state| state.best_match);
+
         // === Additional Events === //
         let mut had_focus_event = false;
 
         // These events are ones that require a specific target and need the tree to be evaluated before selecting the best match
         for (event_type, state) in states {
             if let Some(node) = state.best_match {
-                event_stream.push(Event::new(node, event_type));
+                if event_type == EventType::DragStart {
+                    // The pressed widget only starts being dragged once the mouse moves past the threshold
+                    if self.is_mouse_pressed {
+                        self.active = Some(node);
+                        self.press = Some(PressState {
+                            node,
+                            pressed_at: time,
+                            is_long_press: false,
+                        });
+                        self.drag = Some(DragState {
+                            source: node,
+                            origin: self.current_mouse_position,
+                            last_position: self.current_mouse_position,
+                            is_dragging: false,
+                            hovered: None,
+                            payload: DragPayload::default(),
+                        });
+                    }
+                    continue;
+                }
+
+                if event_type == EventType::Click {
+                    // Releasing after a long press isn't a click
+                    if self
+                        .press
+                        .as_ref()
+                        .map_or(false, |press| press.is_long_press)
+                    {
+                        continue;
+                    }
+                    event_stream.push(Event::new(node, EventType::Click));
+                    event_stream.extend(self.process_double_click(node, config, time));
+                    continue;
+                }
+
+                event_stream.push(Event::new(node, event_type.clone()));
 
                 match event_type {
                     EventType::Focus => {
@@ -215,6 +521,10 @@ impl EventDispatcher {
             }
         }
 
+        // === Timed Events === //
+        let events = self.process_timed_events(has_mouse_moved, hovered, config, time);
+        event_stream.extend(events);
+
         // --- Blur Event --- //
         if !had_focus_event && input_events.contains(&InputEvent::MouseLeftPress) {
             // A mouse press didn't contain a focus event -> blur
@@ -227,18 +537,39 @@ impl EventDispatcher {
         // Apply changes
         self.current_mouse_position = self.next_mouse_position;
 
+        // The cursor is updated every frame since the widgets under it can change without the mouse moving
+        let hovered =
+            pointer_capture.or_else(|| Self::hit_test(widget_manager, self.current_mouse_position));
+        self.cursor_icon = Self::resolve_cursor_icon(widget_manager, hovered);
+        self.hovered = hovered;
+        if !self.is_mouse_pressed {
+            self.active = None;
+        }
+
+        for event in event_stream.iter_mut() {
+            event.modifiers = self.modifiers;
+        }
+
         event_stream
     }
 
-    fn process_pointer_events(&mut self, input_event: &InputEvent, tree_node: TreeNode, states: &mut HashMap<EventType, EventState>, widget_manager: &WidgetManager) -> Vec<Event> {
+    fn process_pointer_events(
+        &mut self,
+        input_event: &InputEvent,
+        tree_node: TreeNode,
+        states: &mut HashMap<EventType, EventState>,
+        widget_manager: &WidgetManager,
+        is_captured: bool,
+    ) -> Vec<Event> {
         let mut event_stream = Vec::<Event>::new();
         let (node, depth) = tree_node;
 
         match input_event {
             InputEvent::MouseMoved(point) => {
                 if let Some(layout) = widget_manager.get_layout(&node) {
-                    let was_contained = layout.contains(&self.current_mouse_position);
-                    let is_contained = layout.contains(point);
+                    let was_contained = is_captured
+                        || widget_manager.contains_point(&node, &self.current_mouse_position);
+                    let is_contained = is_captured || widget_manager.contains_point(&node, point);
                     if was_contained != is_contained {
                         if was_contained {
                             event_stream.push(Event::new(node, EventType::MouseOut));
@@ -261,8 +592,11 @@ impl EventDispatcher {
                 self.is_mouse_pressed = true;
 
                 if let Some(layout) = widget_manager.get_layout(&node) {
-                    if layout.contains(&self.current_mouse_position) {
+                    if is_captured
+                        || widget_manager.contains_point(&node, &self.current_mouse_position)
+                    {
                         event_stream.push(Event::new(node, EventType::MouseDown));
+                        Self::update_state(states, (node, depth), layout, EventType::DragStart);
 
                         if let Some(widget) = widget_manager.current_widgets.get(node).unwrap() {
                             if widget.focusable() {
@@ -277,7 +611,9 @@ impl EventDispatcher {
                 self.is_mouse_pressed = false;
 
                 if let Some(layout) = widget_manager.get_layout(&node) {
-                    if layout.contains(&self.current_mouse_position) {
+                    if is_captured
+                        || widget_manager.contains_point(&node, &self.current_mouse_position)
+                    {
                         event_stream.push(Event::new(node, EventType::MouseUp));
 
                         if Self::contains_event(
@@ -290,22 +626,522 @@ impl EventDispatcher {
                     }
                 }
             }
+            InputEvent::MouseRightPress | InputEvent::MouseMiddlePress => {
+                if let Some(layout) = widget_manager.get_layout(&node) {
+                    if is_captured
+                        || widget_manager.contains_point(&node, &self.current_mouse_position)
+                    {
+                        let event_type = if matches!(input_event, InputEvent::MouseRightPress) {
+                            EventType::RightMouseDown
+                        } else {
+                            EventType::MiddleMouseDown
+                        };
+                        event_stream.push(Event::new(node, event_type));
+                    }
+                }
+            }
+            InputEvent::MouseRightRelease | InputEvent::MouseMiddleRelease => {
+                if let Some(layout) = widget_manager.get_layout(&node) {
+                    if is_captured
+                        || widget_manager.contains_point(&node, &self.current_mouse_position)
+                    {
+                        let (up, down, click) =
+                            if matches!(input_event, InputEvent::MouseRightRelease) {
+                                (
+                                    EventType::RightMouseUp,
+                                    EventType::RightMouseDown,
+                                    EventType::RightClick,
+                                )
+                            } else {
+                                (
+                                    EventType::MiddleMouseUp,
+                                    EventType::MiddleMouseDown,
+                                    EventType::MiddleClick,
+                                )
+                            };
+                        event_stream.push(Event::new(node, up));
+
+                        if Self::contains_event(&self.previous_events, &node, &down) {
+                            Self::update_state(states, (node, depth), layout, click);
+                        }
+                    }
+                }
+            }
+            // A NaN delta would make the event type unequal to itself (see `impl Eq for EventType`)
+            InputEvent::Scroll { delta_x, delta_y } if !delta_x.is_nan() && !delta_y.is_nan() => {
+                if let Some(layout) = widget_manager.get_layout(&node) {
+                    if is_captured
+                        || widget_manager.contains_point(&node, &self.current_mouse_position)
+                    {
+                        // Only the topmost widget under the cursor receives the scroll, which then bubbles up
+                        Self::update_state(
+                            states,
+                            (node, depth),
+                            layout,
+                            EventType::Scroll {
+                                delta_x: *delta_x,
+                                delta_y: *delta_y,
+                            },
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        event_stream
+    }
+
+    fn process_navigation_events(
+        &mut self,
+        input_event: &InputEvent,
+        widget_manager: &WidgetManager,
+    ) -> Vec<Event> {
+        let mut event_stream = Vec::new();
+        match input_event {
+            InputEvent::Navigate(direction) => {
+                let next_focus = self.next_directional_focus(widget_manager, *direction);
+                if next_focus.is_some() {
+                    event_stream.extend(self.change_focus(next_focus));
+                }
+            }
+            InputEvent::Confirm => {
+                if let Some(current_focus) = self.current_focus {
+                    event_stream.push(Event::new(current_focus, EventType::Click));
+                }
+            }
+            InputEvent::Back => {
+                if let Some(current_focus) = self.current_focus {
+                    event_stream.push(Event::new(current_focus, EventType::Back));
+                }
+            }
+            _ => {}
+        }
+
+        event_stream
+    }
+
+    /// Returns the focusable widget nearest to the current focus in the given direction
+    ///
+    /// Distances are measured between the centers of the widgets' layouts. Widgets that are out of line with the
+    /// current focus are penalized so that navigation prefers staying in the same row or column. Without a current
+    /// focus, the first widget in the Tab order is returned.
+    fn next_directional_focus(
+        &self,
+        widget_manager: &WidgetManager,
+        direction: NavigationDirection,
+    ) -> Option<Index> {
+        let candidates = Self::tab_order(widget_manager);
+        let (current_focus, current_layout) = match self.current_focus.and_then(|current_focus| {
+            Some((current_focus, *widget_manager.get_layout(&current_focus)?))
+        }) {
+            Some(current) => current,
+            None => return candidates.first().copied(),
+        };
+
+        let center = |layout: &Rect| {
+            (
+                layout.posx + layout.width / 2.0,
+                layout.posy + layout.height / 2.0,
+            )
+        };
+        let from = center(&current_layout);
+
+        candidates
+            .into_iter()
+            .filter(|index| *index != current_focus)
+            .filter_map(|index| {
+                let to = center(widget_manager.get_layout(&index)?);
+                let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+                // The distance along the direction of travel and the distance across it
+                let (along, across) = match direction {
+                    NavigationDirection::Up => (-dy, dx),
+                    NavigationDirection::Down => (dy, dx),
+                    NavigationDirection::Left => (-dx, dy),
+                    NavigationDirection::Right => (dx, dy),
+                };
+                if along <= 0.0 {
+                    return None;
+                }
+                Some((along + across.abs() * 2.0, index))
+            })
+            .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(_, index)| index)
+    }
+
+    /// Returns the focusable widgets in the order they're cycled through with Tab
+    ///
+    /// See [Widget::tab_index](crate::Widget::tab_index) for how the order is determined.
+    fn tab_order(widget_manager: &WidgetManager) -> Vec<Index> {
+        let mut order: Vec<(Option<i32>, Index)> = widget_manager
+            .tree
+            .flatten()
+            .into_iter()
+            .filter_map(|index| {
+                let widget = widget_manager.current_widgets.get(index)?.as_ref()?;
+                if !widget.focusable() {
+                    return None;
+                }
+                match widget.tab_index() {
+                    Some(tab_index) if tab_index < 0 => None,
+                    tab_index => Some((tab_index, index)),
+                }
+            })
+            .collect();
+
+        // Stable, so widgets with the same tab index stay in tree order
+        order.sort_by_key(|(tab_index, _)| match tab_index {
+            Some(tab_index) if *tab_index > 0 => (0, *tab_index),
+            _ => (1, 0),
+        });
+
+        order.into_iter().map(|(_, index)| index).collect()
+    }
+
+    /// Returns the widget that should receive focus when pressing Tab (or Shift+Tab when `reverse` is true)
+    fn next_tab_focus(&self, widget_manager: &WidgetManager, reverse: bool) -> Option<Index> {
+        let order = Self::tab_order(widget_manager);
+        if order.is_empty() {
+            return None;
+        }
+
+        let current = self
+            .current_focus
+            .and_then(|current_focus| order.iter().position(|index| *index == current_focus));
+        let next = match (current, reverse) {
+            (Some(current), false) => (current + 1) % order.len(),
+            (Some(current), true) => (current + order.len() - 1) % order.len(),
+            (None, false) => 0,
+            (None, true) => order.len() - 1,
+        };
+
+        Some(order[next])
+    }
+
+    /// Updates the current focus, returning the events needed to notify the affected widgets
+    fn change_focus(&mut self, next_focus: Option<Index>) -> Vec<Event> {
+        let mut events = Vec::new();
+        if self.current_focus == next_focus {
+            return events;
+        }
+
+        if let Some(current_focus) = self.current_focus {
+            events.push(Event::new(current_focus, EventType::Blur));
+        }
+        if let Some(next_focus) = next_focus {
+            events.push(Event::new(next_focus, EventType::Focus));
+        }
+        self.current_focus = next_focus;
+
+        events
+    }
+
+    /// Tracks each touch separately, sending its events to the widget it started on
+    ///
+    /// Like a mouse press, starting a touch focuses the touched widget (or its nearest focusable ancestor), and
+    /// lifting the finger over the widget the touch started on clicks it.
+    fn process_touch_events(
+        &mut self,
+        input_event: &InputEvent,
+        widget_manager: &WidgetManager,
+        pointer_capture: Option<Index>,
+    ) -> Vec<Event> {
+        let mut event_stream = Vec::new();
+        match *input_event {
+            InputEvent::TouchStart { id, position } => {
+                let target = pointer_capture.or_else(|| Self::hit_test(widget_manager, position));
+                if let Some(target) = target {
+                    event_stream.push(Event::new(target, EventType::TouchStart { id, position }));
+                }
+
+                let mut focusable = target;
+                while let Some(index) = focusable {
+                    let is_focusable = matches!(widget_manager.current_widgets.get(index), Some(Some(widget)) if widget.focusable());
+                    if is_focusable {
+                        break;
+                    }
+                    focusable = widget_manager.node_tree.get_parent(index);
+                }
+                event_stream.extend(self.change_focus(focusable));
+
+                self.touches.insert(id, TouchState { target, position });
+            }
+            InputEvent::TouchMove { id, position } => {
+                if let Some(touch) = self.touches.get_mut(&id) {
+                    touch.position = position;
+                    if let Some(target) = touch.target {
+                        event_stream
+                            .push(Event::new(target, EventType::TouchMove { id, position }));
+                    }
+                }
+            }
+            InputEvent::TouchEnd { id, position } => {
+                if let Some(touch) = self.touches.remove(&id) {
+                    if let Some(target) = touch.target {
+                        event_stream.push(Event::new(target, EventType::TouchEnd { id, position }));
+
+                        let released_on =
+                            pointer_capture.or_else(|| Self::hit_test(widget_manager, position));
+                        if released_on == Some(target) {
+                            event_stream.push(Event::new(target, EventType::Click));
+                        }
+                    }
+                }
+            }
+            InputEvent::TouchCancel { id } => {
+                if let Some(touch) = self.touches.remove(&id) {
+                    if let Some(target) = touch.target {
+                        event_stream.push(Event::new(target, EventType::TouchCancel { id }));
+                    }
+                }
+            }
             _ => {}
         }
 
         event_stream
     }
 
+    /// Returns the topmost widget at the given position, following the same rules as mouse events
+    fn hit_test(widget_manager: &WidgetManager, position: (f32, f32)) -> Option<Index> {
+        let root = widget_manager.node_tree.root_node?;
+        let mut states = HashMap::new();
+        let mut stack: Vec<TreeNode> = vec![(root, 0)];
+        while let Some((current, depth)) = stack.pop() {
+            let pointer_events = Self::pointer_events(widget_manager, current);
+            if matches!(pointer_events, PointerEvents::All | PointerEvents::SelfOnly) {
+                if let Some(layout) = widget_manager.get_layout(&current) {
+                    if widget_manager.contains_point(&current, &position) {
+                        Self::update_state(&mut states, (current, depth), layout, EventType::Hover);
+                    }
+                }
+            }
+
+            if matches!(
+                pointer_events,
+                PointerEvents::All | PointerEvents::ChildrenOnly
+            ) {
+                if let Some(children) = widget_manager.node_tree.children.get(&current) {
+                    for child in children {
+                        stack.push((*child, depth + 1));
+                    }
+                }
+            }
+        }
+
+        states
+            .get(&EventType::Hover)
+            .and_then(|state| state.best_match)
+    }
+
+    /// Returns the resolved [PointerEvents] style of the given widget
+    fn pointer_events(widget_manager: &WidgetManager, index: Index) -> PointerEvents {
+        // Nodes hold the styles merged with the parent's, in which nothing is left to inherit
+        if let Some(Some(node)) = widget_manager.nodes.get(index) {
+            return node.styles.pointer_events.resolve();
+        }
+        match widget_manager.current_widgets.get(index) {
+            Some(Some(widget)) => widget
+                .get_styles()
+                .map(|styles| styles.pointer_events)
+                .filter(|pointer_events| *pointer_events != StyleProp::Inherit)
+                .map(|pointer_events| pointer_events.resolve())
+                .unwrap_or_default(),
+            _ => PointerEvents::default(),
+        }
+    }
+
+    /// Returns the cursor icon of the given widget, which is inherited from its ancestors if it doesn't set one
+    fn resolve_cursor_icon(widget_manager: &WidgetManager, hovered: Option<Index>) -> CursorIcon {
+        // Nodes hold the styles merged with the widget's classes and its parent's styles, so the nearest one
+        // already has the inherited cursor
+        let mut current = hovered;
+        while let Some(index) = current {
+            if let Some(Some(node)) = widget_manager.nodes.get(index) {
+                return node.styles.cursor.resolve();
+            }
+            current = widget_manager.node_tree.get_parent(index);
+        }
+
+        CursorIcon::Default
+    }
+
+    /// Updates the current drag, returning the drag events it generates
+    ///
+    /// The widget being dragged over is the one receiving [Hover](EventType::Hover), so a widget following the
+    /// mouse during the drag should set its `pointer_events` to `None` to not hide the widgets beneath it.
+    fn process_drag_events(
+        &mut self,
+        input_events: &[InputEvent],
+        states: &mut HashMap<EventType, EventState>,
+        config: &EventConfig,
+    ) -> Vec<Event> {
+        let mut event_stream = Vec::new();
+        let drag = match &mut self.drag {
+            Some(drag) => drag,
+            None => return event_stream,
+        };
+
+        if input_events
+            .iter()
+            .any(|input_event| matches!(input_event, InputEvent::MouseMoved(..)))
+        {
+            let position = self.next_mouse_position;
+            if !drag.is_dragging {
+                let (dx, dy) = (position.0 - drag.origin.0, position.1 - drag.origin.1);
+                if (dx * dx + dy * dy).sqrt() >= config.drag_threshold {
+                    drag.is_dragging = true;
+                    event_stream.push(Event::new(drag.source, EventType::DragStart));
+                }
+            }
+
+            if drag.is_dragging {
+                let delta = (
+                    position.0 - drag.last_position.0,
+                    position.1 - drag.last_position.1,
+                );
+                event_stream.push(Event::new(drag.source, EventType::Drag { delta }));
+                drag.last_position = position;
+
+                let hovered = states
+                    .get(&EventType::Hover)
+                    .and_then(|state| state.best_match);
+                if hovered != drag.hovered {
+                    if let Some(previous) = drag.hovered {
+                        event_stream.push(Event::new(previous, EventType::DragLeave));
+                    }
+                    if let Some(hovered) = hovered {
+                        event_stream.push(Event::new(hovered, EventType::DragEnter));
+                    }
+                    drag.hovered = hovered;
+                }
+            }
+        }
+
+        if input_events.contains(&InputEvent::MouseLeftRelease) {
+            if drag.is_dragging {
+                if let Some(hovered) = drag.hovered {
+                    event_stream.push(Event::new(
+                        hovered,
+                        EventType::Drop {
+                            payload: drag.payload.clone(),
+                        },
+                    ));
+                }
+                event_stream.push(Event::new(drag.source, EventType::DragEnd));
+
+                // Releasing after a drag isn't a click
+                states.remove(&EventType::Click);
+            }
+            self.drag = None;
+        }
+
+        event_stream
+    }
+
+    /// Returns a [DoubleClick](EventType::DoubleClick) event if the given click follows another one closely enough
+    fn process_double_click(
+        &mut self,
+        node: Index,
+        config: &EventConfig,
+        time: Instant,
+    ) -> Option<Event> {
+        let position = self.current_mouse_position;
+        let is_double_click = self.last_click.as_ref().map_or(false, |last_click| {
+            let (dx, dy) = (
+                position.0 - last_click.position.0,
+                position.1 - last_click.position.1,
+            );
+            last_click.node == node
+                && time.saturating_duration_since(last_click.time) <= config.double_click_interval
+                && (dx * dx + dy * dy).sqrt() <= config.double_click_distance
+        });
+
+        if is_double_click {
+            // A third click starts over instead of being another double click
+            self.last_click = None;
+            Some(Event::new(node, EventType::DoubleClick))
+        } else {
+            self.last_click = Some(ClickState {
+                node,
+                time,
+                position,
+            });
+            None
+        }
+    }
+
+    /// Fires the events that need the mouse to stay in place for a while: [LongPress](EventType::LongPress) and
+    /// [HoverStart](EventType::HoverStart)
+    ///
+    /// `hovered` is the widget under the mouse, which is only known when the mouse moved.
+    fn process_timed_events(
+        &mut self,
+        has_mouse_moved: bool,
+        hovered: Option<Index>,
+        config: &EventConfig,
+        time: Instant,
+    ) -> Vec<Event> {
+        let mut event_stream = Vec::new();
+
+        // --- Long Press --- //
+        let is_dragging = self.drag.as_ref().map_or(false, |drag| drag.is_dragging);
+        if !self.is_mouse_pressed || is_dragging {
+            self.press = None;
+        }
+        if let Some(press) = &mut self.press {
+            if !press.is_long_press
+                && time.saturating_duration_since(press.pressed_at) >= config.long_press_duration
+            {
+                press.is_long_press = true;
+                event_stream.push(Event::new(press.node, EventType::LongPress));
+            }
+        }
+
+        // --- Hover Start --- //
+        if has_mouse_moved && self.hover.as_ref().map(|hover| hover.node) != hovered {
+            self.hover = hovered.map(|node| HoverState {
+                node,
+                entered_at: time,
+                has_started: false,
+            });
+        }
+        if let Some(hover) = &mut self.hover {
+            if !hover.has_started
+                && time.saturating_duration_since(hover.entered_at) >= config.hover_delay
+            {
+                hover.has_started = true;
+                event_stream.push(Event::new(hover.node, EventType::HoverStart));
+            }
+        }
+
+        event_stream
+    }
+
+    /// Keeps track of which modifier keys are held down
+    fn update_modifiers(&mut self, key: KeyCode, is_pressed: bool) {
+        match key {
+            KeyCode::LShift | KeyCode::RShift => self.modifiers.shift = is_pressed,
+            KeyCode::LControl | KeyCode::RControl => self.modifiers.control = is_pressed,
+            KeyCode::LAlt | KeyCode::RAlt => self.modifiers.alt = is_pressed,
+            KeyCode::LWin | KeyCode::RWin => self.modifiers.super_key = is_pressed,
+            _ => {}
+        }
+    }
+
     fn process_keyboard_events(&mut self, input_event: &InputEvent, _states: &mut HashMap<EventType, EventState>, _widget_manager: &WidgetManager) -> Vec<Event> {
         let mut event_stream = Vec::new();
         if let Some(current_focus) = self.current_focus {
             match input_event {
-                InputEvent::CharEvent { c } => event_stream.push(
-                    Event::new(current_focus, EventType::CharInput { c: *c })
-                ),
-                InputEvent::Keyboard { key } => event_stream.push(
-                    Event::new(current_focus, EventType::KeyboardInput { key: *key })
-                ),
+                InputEvent::CharEvent { c } => {
+                    event_stream.push(Event::new(current_focus, EventType::CharInput { c: *c }))
+                }
+                InputEvent::Keyboard { key, is_pressed } => event_stream.push(Event::new(
+                    current_focus,
+                    EventType::KeyboardInput {
+                        key: *key,
+                        is_pressed: *is_pressed,
+                    },
+                )),
                 _ => {}
             }
         }
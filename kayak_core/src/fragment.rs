@@ -3,7 +3,7 @@ use derivative::*;
 use crate::{context::KayakContext, styles::Style, Index, Widget};
 
 #[derive(Derivative)]
-#[derivative(Default, Debug, PartialEq)]
+#[derivative(Default, Debug, PartialEq, Clone)]
 pub struct Fragment {
     pub id: Index,
     #[derivative(Default(value = "None"))]
@@ -56,6 +56,9 @@ impl Widget for Fragment {
         // Evaluate changes to the tree.
         let changes = context.widget_manager.tree.diff_children(&tree, parent_id);
 
-        context.widget_manager.tree.merge(&tree, parent_id, changes);
+        let removed = context.widget_manager.tree.merge(&tree, parent_id, changes);
+
+        // Clean up any widgets that are no longer part of the tree.
+        context.unmount(removed);
     }
 }
@@ -5,14 +5,66 @@ pub enum InputEvent {
     MouseMoved((f32, f32)),
     MouseLeftPress,
     MouseLeftRelease,
-    CharEvent { c: char },
-    Keyboard { key: KeyCode },
+    MouseRightPress,
+    MouseRightRelease,
+    MouseMiddlePress,
+    MouseMiddleRelease,
+    /// The mouse wheel was scrolled, in pixels (positive `delta_y` scrolls up)
+    ///
+    /// Scrolls with a NaN delta are ignored.
+    Scroll {
+        delta_x: f32,
+        delta_y: f32,
+    },
+    CharEvent {
+        c: char,
+    },
+    /// A key was pressed, or released when `is_pressed` is `false`
+    ///
+    /// Backends must send releases as well, which keep the state of the [modifier keys](crate::Modifiers) up to
+    /// date, and can't leave `is_pressed` out: it used to be implied that every keyboard event was a press.
+    Keyboard {
+        key: KeyCode,
+        is_pressed: bool,
+    },
+    /// A finger touched the screen, `id` identifies it until it is lifted
+    TouchStart {
+        id: u64,
+        position: (f32, f32),
+    },
+    TouchMove {
+        id: u64,
+        position: (f32, f32),
+    },
+    TouchEnd {
+        id: u64,
+        position: (f32, f32),
+    },
+    /// The touch was interrupted by the system (e.g., by a gesture)
+    TouchCancel {
+        id: u64,
+    },
+    /// Moves focus to the nearest focusable widget in the given direction (e.g., from a gamepad's D-pad)
+    Navigate(NavigationDirection),
+    /// Activates the focused widget, which receives a [Click](crate::EventType::Click)
+    Confirm,
+    /// Sends a [Back](crate::EventType::Back) event to the focused widget
+    Back,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NavigationDirection {
+    Up,
+    Down,
+    Left,
+    Right,
 }
 
 pub enum InputEventCategory {
     Mouse,
     Keyboard,
-    // TODO: Gamepad, etc.
+    Touch,
+    Navigation,
 }
 
 impl InputEvent {
@@ -22,9 +74,23 @@ impl InputEvent {
             Self::MouseMoved(..) => InputEventCategory::Mouse,
             Self::MouseLeftPress => InputEventCategory::Mouse,
             Self::MouseLeftRelease => InputEventCategory::Mouse,
+            Self::MouseRightPress => InputEventCategory::Mouse,
+            Self::MouseRightRelease => InputEventCategory::Mouse,
+            Self::MouseMiddlePress => InputEventCategory::Mouse,
+            Self::MouseMiddleRelease => InputEventCategory::Mouse,
+            Self::Scroll { .. } => InputEventCategory::Mouse,
             // Keyboard events
             Self::CharEvent {..} => InputEventCategory::Keyboard,
             Self::Keyboard {..} => InputEventCategory::Keyboard,
+            // Touch events
+            Self::TouchStart { .. } => InputEventCategory::Touch,
+            Self::TouchMove { .. } => InputEventCategory::Touch,
+            Self::TouchEnd { .. } => InputEventCategory::Touch,
+            Self::TouchCancel { .. } => InputEventCategory::Touch,
+            // Navigation events
+            Self::Navigate(..) => InputEventCategory::Navigation,
+            Self::Confirm => InputEventCategory::Navigation,
+            Self::Back => InputEventCategory::Navigation,
         }
     }
 }
@@ -99,6 +99,35 @@ impl LayoutCache {
 
         self.visible.insert(node_index, true);
     }
+
+    pub fn remove(&mut self, node_index: Index) {
+        self.rect.remove(&node_index);
+
+        self.space.remove(&node_index);
+
+        self.child_width_max.remove(&node_index);
+        self.child_height_max.remove(&node_index);
+        self.child_width_sum.remove(&node_index);
+        self.child_height_sum.remove(&node_index);
+
+        self.grid_row_max.remove(&node_index);
+        self.grid_col_max.remove(&node_index);
+
+        self.horizontal_free_space.remove(&node_index);
+        self.horizontal_stretch_sum.remove(&node_index);
+
+        self.vertical_free_space.remove(&node_index);
+        self.vertical_stretch_sum.remove(&node_index);
+
+        self.stack_first_child.remove(&node_index);
+        self.stack_last_child.remove(&node_index);
+
+        self.size.remove(&node_index);
+
+        self.geometry_changed.remove(&node_index);
+
+        self.visible.remove(&node_index);
+    }
 }
 
 impl Cache for LayoutCache {
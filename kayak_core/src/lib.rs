@@ -1,6 +1,10 @@
+mod background;
 mod binding;
+mod box_shadow;
 pub mod color;
 pub mod context;
+mod cursor;
+mod effect;
 pub mod event;
 pub mod fragment;
 pub(crate) mod generational_arena;
@@ -11,26 +15,35 @@ mod multi_state;
 pub mod node;
 pub mod render_command;
 pub mod render_primitive;
+pub mod snapshot;
+mod style_parser;
 pub mod styles;
+mod stylesheet;
+pub mod testing;
+mod transform;
 pub mod tree;
 mod vec;
 pub mod widget;
 pub mod widget_manager;
-mod cursor;
-mod event_dispatcher;
 
 use std::sync::{Arc, RwLock};
 
+pub use background::{Background, ColorStop};
 pub use binding::*;
-pub use cursor::PointerEvents;
+pub use box_shadow::BoxShadow;
 pub use color::Color;
 pub use context::*;
+pub use cursor::{CursorIcon, PointerEvents};
+pub use effect::{Cleanup, EffectCleanup};
 pub use event::*;
 pub use fragment::Fragment;
 pub use generational_arena::{Arena, Index};
 pub use input_event::*;
 pub use keys::KeyCode;
 pub use resources::Resources;
+pub use style_parser::{parse_stylesheet, ParseStyleValue, StyleParseError};
+pub use stylesheet::{ClassStyle, StyleSheet, StyleState};
+pub use transform::{Affine, Transform};
 pub use tree::{Tree, WidgetTree};
 pub use vec::VecTracker;
 pub use widget::Widget;
@@ -47,13 +60,40 @@ pub struct OnEvent(
     pub  Arc<
         RwLock<dyn FnMut(&mut crate::context::KayakContext, &mut Event) + Send + Sync + 'static>,
     >,
+    /// Whether the handler is also called during the [capture phase](EventPhase::Capture)
+    bool,
 );
 
 impl OnEvent {
+    /// Creates an event handler called when the event reaches its target and while it bubbles up
     pub fn new<F: FnMut(&mut crate::context::KayakContext, &mut Event) + Send + Sync + 'static>(
         f: F,
     ) -> OnEvent {
-        OnEvent(Arc::new(RwLock::new(f)))
+        OnEvent(Arc::new(RwLock::new(f)), false)
+    }
+
+    /// Creates an event handler that is also called during the capture phase
+    ///
+    /// This lets a widget handle events targeting its descendants before they do (and stop them with
+    /// [`event.stop_propagation()`](Event::stop_propagation)). Use [`event.phase`](Event::phase) to tell
+    /// the phases apart.
+    pub fn with_capture<
+        F: FnMut(&mut crate::context::KayakContext, &mut Event) + Send + Sync + 'static,
+    >(
+        f: F,
+    ) -> OnEvent {
+        OnEvent(Arc::new(RwLock::new(f)), true)
+    }
+
+    /// Calls the handler, unless the event is in a phase it doesn't listen to
+    pub fn call(&self, context: &mut crate::context::KayakContext, event: &mut Event) {
+        if event.phase == EventPhase::Capture && !self.1 {
+            return;
+        }
+
+        if let Ok(mut on_event) = self.0.write() {
+            on_event(context, event);
+        }
     }
 }
 
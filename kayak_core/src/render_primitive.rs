@@ -1,8 +1,11 @@
 use crate::{
+    background::Background,
+    box_shadow::BoxShadow,
     color::Color,
     layout_cache::{Rect, Space},
     render_command::RenderCommand,
     styles::{Style, StyleProp},
+    transform::Affine,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -13,8 +16,19 @@ pub enum RenderPrimitive {
     },
     Quad {
         layout: Rect,
-        background_color: Color,
+        background: Background,
         border_radius: (f32, f32, f32, f32),
+        /// The width of the border on each side: top, right, bottom and left
+        border: (f32, f32, f32, f32),
+        border_color: Color,
+    },
+    /// The shadow of a quad, built from its `box_shadow` style
+    ///
+    /// The layout is the one of the quad casting the shadow (see [BoxShadow::bounds] for the area it covers).
+    BoxShadow {
+        layout: Rect,
+        border_radius: (f32, f32, f32, f32),
+        box_shadow: BoxShadow,
     },
     Text {
         layout: Rect,
@@ -26,11 +40,21 @@ pub enum RenderPrimitive {
     Image {
         layout: Rect,
         handle: u16,
+        opacity: f32,
     },
     NinePatch {
         border: Space,
         layout: Rect,
         handle: u16,
+        opacity: f32,
+    },
+    /// A primitive of a widget with a `transform` style, or of one of its children
+    ///
+    /// The layout of the wrapped primitive is left untransformed. The transform combines the ones of the widget and
+    /// its parents, mapping the layout to where the primitive is drawn.
+    Transformed {
+        transform: Affine,
+        primitive: Box<RenderPrimitive>,
     },
 }
 
@@ -39,22 +63,63 @@ impl RenderPrimitive {
         match self {
             RenderPrimitive::Clip { layout, .. } => *layout = new_layout,
             RenderPrimitive::Quad { layout, .. } => *layout = new_layout,
+            RenderPrimitive::BoxShadow { layout, .. } => *layout = new_layout,
             RenderPrimitive::Text { layout, .. } => *layout = new_layout,
             RenderPrimitive::Image { layout, .. } => *layout = new_layout,
             RenderPrimitive::NinePatch { layout, .. } => *layout = new_layout,
+            RenderPrimitive::Transformed { primitive, .. } => primitive.set_layout(new_layout),
             _ => (),
         }
     }
+
+    /// Makes the primitive more transparent by multiplying the alpha of its colors with `opacity`
+    pub fn apply_opacity(&mut self, opacity: f32) {
+        let fade = |color: &mut Color| color.a *= opacity;
+        match self {
+            RenderPrimitive::Quad {
+                background,
+                border_color,
+                ..
+            } => {
+                match background {
+                    Background::Color(color) => fade(color),
+                    Background::LinearGradient { stops, .. }
+                    | Background::RadialGradient { stops, .. } => {
+                        stops.iter_mut().for_each(|stop| fade(&mut stop.color))
+                    }
+                }
+                fade(border_color);
+            }
+            RenderPrimitive::BoxShadow { box_shadow, .. } => fade(&mut box_shadow.color),
+            RenderPrimitive::Text { color, .. } => fade(color),
+            RenderPrimitive::Image {
+                opacity: image_opacity,
+                ..
+            }
+            | RenderPrimitive::NinePatch {
+                opacity: image_opacity,
+                ..
+            } => *image_opacity *= opacity,
+            RenderPrimitive::Transformed { primitive, .. } => primitive.apply_opacity(opacity),
+            RenderPrimitive::Empty | RenderPrimitive::Clip { .. } => {}
+        }
+    }
 }
 
 impl From<&Style> for RenderPrimitive {
     fn from(style: &Style) -> Self {
         let render_command = style.render_command.resolve();
 
-        let background_color = if matches!(style.background_color, StyleProp::Default) {
+        let background = match &style.background {
+            StyleProp::Value(background) => background.clone(),
+            _ if matches!(style.background_color, StyleProp::Default) => Background::default(),
+            _ => Background::Color(style.background_color.resolve()),
+        };
+
+        let border_color = if matches!(style.border_color, StyleProp::Default) {
             Color::TRANSPARENT
         } else {
-            style.background_color.resolve()
+            style.border_color.resolve()
         };
 
         match render_command {
@@ -64,8 +129,10 @@ impl From<&Style> for RenderPrimitive {
                 layout: Rect::default(),
             },
             RenderCommand::Quad => Self::Quad {
-                background_color: background_color,
+                background,
                 border_radius: style.border_radius.resolve(),
+                border: style.border.resolve(),
+                border_color,
                 layout: Rect::default(),
             },
             RenderCommand::Text {
@@ -82,11 +149,13 @@ impl From<&Style> for RenderPrimitive {
             RenderCommand::Image { handle } => Self::Image {
                 layout: Rect::default(),
                 handle,
+                opacity: 1.0,
             },
             RenderCommand::NinePatch { handle, border } => Self::NinePatch {
                 border,
                 layout: Rect::default(),
                 handle,
+                opacity: 1.0,
             },
         }
     }
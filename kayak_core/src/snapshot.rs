@@ -0,0 +1,566 @@
+//! Stable, human-readable snapshots of render primitives for golden-file tests
+//!
+//! A snapshot has one line per renderable primitive, in render order:
+//!
+//! ```text
+//! Clip position=0,0 size=800x600 z=-0.1
+//! Quad position=0,0 size=100x50 z=2 color=#141719ff radius=5,5,5,5
+//! Text position=16,5 size=67x39 z=3 color=#ffffffff font=0 font_size=24 content="Count!"
+//! ```
+//!
+//! Numbers are rounded to two decimals and colors are written as 8-bit hex, so snapshots don't change due to
+//! floating point noise.
+
+use std::{fmt, path::Path};
+
+use crate::{background::Background, color::Color, render_primitive::RenderPrimitive};
+
+/// The environment variable that makes [assert_snapshot] overwrite the stored snapshots
+pub const UPDATE_SNAPSHOTS_VAR: &str = "KAYAK_UPDATE_SNAPSHOTS";
+
+/// Serializes the given primitives into a snapshot
+///
+/// [Empty](RenderPrimitive::Empty) primitives are skipped since they don't render anything.
+pub fn snapshot(primitives: &[RenderPrimitive]) -> String {
+    let mut snapshot = String::new();
+    for primitive in primitives {
+        if let Some(line) = snapshot_line(primitive) {
+            snapshot.push_str(&line);
+            snapshot.push('\n');
+        }
+    }
+    snapshot
+}
+
+fn snapshot_line(primitive: &RenderPrimitive) -> Option<String> {
+    let line = match primitive {
+        RenderPrimitive::Empty => return None,
+        RenderPrimitive::Clip { layout } => format!("Clip {}", format_layout(layout)),
+        RenderPrimitive::Quad {
+            layout,
+            background,
+            border_radius,
+            border,
+            border_color,
+        } => {
+            let mut line = format!(
+                "Quad {} {} radius={},{},{},{}",
+                format_layout(layout),
+                format_background(background),
+                format_number(border_radius.0),
+                format_number(border_radius.1),
+                format_number(border_radius.2),
+                format_number(border_radius.3),
+            );
+            // Most quads have no border, which is left out to keep the snapshots short
+            if *border != (0.0, 0.0, 0.0, 0.0) {
+                line.push_str(&format!(
+                    " border={},{},{},{} border_color={}",
+                    format_number(border.0),
+                    format_number(border.1),
+                    format_number(border.2),
+                    format_number(border.3),
+                    format_color(border_color),
+                ));
+            }
+            line
+        }
+        RenderPrimitive::BoxShadow {
+            layout,
+            border_radius,
+            box_shadow,
+        } => format!(
+            "BoxShadow {} radius={},{},{},{} offset={},{} blur={} spread={} color={}{}",
+            format_layout(layout),
+            format_number(border_radius.0),
+            format_number(border_radius.1),
+            format_number(border_radius.2),
+            format_number(border_radius.3),
+            format_number(box_shadow.offset.0),
+            format_number(box_shadow.offset.1),
+            format_number(box_shadow.blur),
+            format_number(box_shadow.spread),
+            format_color(&box_shadow.color),
+            if box_shadow.inset { " inset" } else { "" },
+        ),
+        RenderPrimitive::Text {
+            layout,
+            color,
+            size,
+            content,
+            font,
+        } => format!(
+            "Text {} color={} font={} font_size={} content={:?}",
+            format_layout(layout),
+            format_color(color),
+            font,
+            format_number(*size),
+            content,
+        ),
+        RenderPrimitive::Image {
+            layout,
+            handle,
+            opacity,
+        } => format!(
+            "Image {} handle={}{}",
+            format_layout(layout),
+            handle,
+            format_opacity(*opacity)
+        ),
+        RenderPrimitive::NinePatch {
+            border,
+            layout,
+            handle,
+            opacity,
+        } => format!(
+            "NinePatch {} handle={} border={},{},{},{}{}",
+            format_layout(layout),
+            handle,
+            format_number(border.left),
+            format_number(border.right),
+            format_number(border.top),
+            format_number(border.bottom),
+            format_opacity(*opacity),
+        ),
+        RenderPrimitive::Transformed {
+            transform,
+            primitive,
+        } => {
+            let [a, b, c, d, e, f] = transform.matrix.map(format_number);
+            format!(
+                "{} transform={},{},{},{},{},{}",
+                snapshot_line(primitive)?,
+                a,
+                b,
+                c,
+                d,
+                e,
+                f
+            )
+        }
+    };
+
+    Some(line)
+}
+
+fn format_layout(layout: &crate::layout_cache::Rect) -> String {
+    format!(
+        "position={},{} size={}x{} z={}",
+        format_number(layout.posx),
+        format_number(layout.posy),
+        format_number(layout.width),
+        format_number(layout.height),
+        format_number(layout.z_index),
+    )
+}
+
+fn format_number(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    // Avoid writing "-0"
+    let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+    let formatted = format!("{:.2}", rounded);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Only images carry an opacity, which is left out when they're opaque
+fn format_opacity(opacity: f32) -> String {
+    if opacity < 1.0 {
+        format!(" opacity={}", format_number(opacity))
+    } else {
+        String::new()
+    }
+}
+
+fn format_background(background: &Background) -> String {
+    let stops = || {
+        background
+            .stops()
+            .iter()
+            .map(|stop| {
+                format!(
+                    "{}@{}",
+                    format_color(&stop.color),
+                    format_number(stop.position)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    match background {
+        Background::Color(color) => format!("color={}", format_color(color)),
+        Background::LinearGradient { angle, .. } => {
+            format!("linear_gradient={}deg:{}", format_number(*angle), stops())
+        }
+        Background::RadialGradient { center, .. } => format!(
+            "radial_gradient={},{}:{}",
+            format_number(center.0),
+            format_number(center.1),
+            stops()
+        ),
+    }
+}
+
+fn format_color(color: &Color) -> String {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        channel(color.r),
+        channel(color.g),
+        channel(color.b),
+        channel(color.a),
+    )
+}
+
+/// A single parsed snapshot line
+#[derive(Debug, Clone, PartialEq)]
+struct SnapshotEntry {
+    line: String,
+    kind: String,
+    fields: Vec<(String, String)>,
+}
+
+impl SnapshotEntry {
+    fn parse(line: &str) -> Self {
+        let line = line.trim();
+        let (kind, mut rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut fields = Vec::new();
+
+        loop {
+            rest = rest.trim_start();
+            let (key, value_rest) = match rest.split_once('=') {
+                Some(field) => field,
+                None => break,
+            };
+
+            let value_end = if value_rest.starts_with('"') {
+                // Quoted strings end at the first unescaped quote
+                let mut escaped = false;
+                value_rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|(_, c)| {
+                        let is_end = !escaped && *c == '"';
+                        escaped = !escaped && *c == '\\';
+                        is_end
+                    })
+                    .map(|(index, _)| index + 1)
+                    .unwrap_or(value_rest.len())
+            } else {
+                value_rest.find(' ').unwrap_or(value_rest.len())
+            };
+
+            fields.push((key.to_string(), value_rest[..value_end].to_string()));
+            rest = &value_rest[value_end..];
+        }
+
+        Self {
+            line: line.to_string(),
+            kind: kind.to_string(),
+            fields,
+        }
+    }
+
+    fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field_key, _)| field_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A difference between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotChange {
+    /// A primitive only exists in the new snapshot, at `index`
+    Added { index: usize, line: String },
+    /// A primitive only exists in the old snapshot, at `index`
+    Removed { index: usize, line: String },
+    /// A primitive's position changed, `index` is its position in the new snapshot
+    Moved {
+        index: usize,
+        kind: String,
+        from: String,
+        to: String,
+    },
+    /// A primitive's size changed, `index` is its position in the new snapshot
+    Resized {
+        index: usize,
+        kind: String,
+        from: String,
+        to: String,
+    },
+    /// Any other property of a primitive changed (e.g. its color, z-index or text content)
+    Changed {
+        index: usize,
+        kind: String,
+        field: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for SnapshotChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added { index, line } => write!(f, "+ [{}] {}", index, line),
+            Self::Removed { index, line } => write!(f, "- [{}] {}", index, line),
+            Self::Moved {
+                index,
+                kind,
+                from,
+                to,
+            } => write!(f, "~ [{}] {} moved from {} to {}", index, kind, from, to),
+            Self::Resized {
+                index,
+                kind,
+                from,
+                to,
+            } => write!(f, "~ [{}] {} resized from {} to {}", index, kind, from, to),
+            Self::Changed {
+                index,
+                kind,
+                field,
+                from,
+                to,
+            } => write!(
+                f,
+                "~ [{}] {} {} changed from {} to {}",
+                index, kind, field, from, to
+            ),
+        }
+    }
+}
+
+/// The differences between two snapshots, see [diff_snapshots]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SnapshotDiff {
+    pub changes: Vec<SnapshotChange>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two snapshots and lists which primitives were added, removed, moved, resized or changed
+///
+/// Primitives are matched up by kind in render order (using the longest common subsequence), so inserting or
+/// removing a primitive doesn't report every primitive after it as changed.
+pub fn diff_snapshots(old: &str, new: &str) -> SnapshotDiff {
+    let parse = |snapshot: &str| -> Vec<SnapshotEntry> {
+        snapshot
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(SnapshotEntry::parse)
+            .collect()
+    };
+    let old = parse(old);
+    let new = parse(new);
+
+    // lengths[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i].kind == new[j].kind {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = SnapshotDiff::default();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i].kind == new[j].kind {
+            diff_entries(&old[i], &new[j], j, &mut diff.changes);
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            diff.changes.push(SnapshotChange::Added {
+                index: j,
+                line: new[j].line.clone(),
+            });
+            j += 1;
+        } else {
+            diff.changes.push(SnapshotChange::Removed {
+                index: i,
+                line: old[i].line.clone(),
+            });
+            i += 1;
+        }
+    }
+
+    diff
+}
+
+fn diff_entries(
+    old: &SnapshotEntry,
+    new: &SnapshotEntry,
+    index: usize,
+    changes: &mut Vec<SnapshotChange>,
+) {
+    let mut keys: Vec<&str> = new.fields.iter().map(|(key, _)| key.as_str()).collect();
+    for (key, _) in old.fields.iter() {
+        if !keys.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+
+    for key in keys {
+        let from = old.field(key).unwrap_or("none").to_string();
+        let to = new.field(key).unwrap_or("none").to_string();
+        if from == to {
+            continue;
+        }
+
+        let kind = new.kind.clone();
+        changes.push(match key {
+            "position" => SnapshotChange::Moved {
+                index,
+                kind,
+                from,
+                to,
+            },
+            "size" => SnapshotChange::Resized {
+                index,
+                kind,
+                from,
+                to,
+            },
+            _ => SnapshotChange::Changed {
+                index,
+                kind,
+                field: key.to_string(),
+                from,
+                to,
+            },
+        });
+    }
+}
+
+/// Compares `actual` with the snapshot stored at `path`, panicking with a diff if they differ
+///
+/// Snapshots are only written when the `KAYAK_UPDATE_SNAPSHOTS` environment variable is set, which is how new
+/// snapshots are created as well. Otherwise a missing snapshot fails, so that forgetting to commit one doesn't
+/// make the test pass.
+pub fn assert_snapshot<P: AsRef<Path>>(path: P, actual: &str) {
+    check_snapshot(
+        path.as_ref(),
+        actual,
+        std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some(),
+    );
+}
+
+fn check_snapshot(path: &Path, actual: &str, update: bool) {
+    if update {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, actual).unwrap();
+        return;
+    }
+
+    if !path.exists() {
+        panic!(
+            "Snapshot {} doesn't exist (set {} to create it)",
+            path.display(),
+            UPDATE_SNAPSHOTS_VAR
+        );
+    }
+    let expected = std::fs::read_to_string(path).unwrap();
+    let diff = diff_snapshots(&expected, actual);
+    if !diff.is_empty() {
+        panic!(
+            "Snapshot {} doesn't match (set {} to update it):\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS_VAR,
+            diff
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "doesn't exist")]
+fn test_missing_snapshot_fails() {
+    let path = std::env::temp_dir().join("kayak_missing_snapshot.snap");
+    let _ = std::fs::remove_file(&path);
+    check_snapshot(&path, "Clip position=0,0 size=800x600 z=-0.1\n", false);
+}
+
+#[test]
+fn test_snapshot_diff() {
+    use crate::layout_cache::Rect;
+
+    let quad = |posx: f32, width: f32| RenderPrimitive::Quad {
+        layout: Rect {
+            posx,
+            posy: 0.0,
+            width,
+            height: 50.0,
+            z_index: 1.0,
+        },
+        background: Background::Color(Color::BLACK),
+        border_radius: (0.0, 0.0, 0.0, 0.0),
+        border: (0.0, 0.0, 0.0, 0.0),
+        border_color: Color::TRANSPARENT,
+    };
+    let text = |content: &str| RenderPrimitive::Text {
+        layout: Rect::default(),
+        color: Color::WHITE,
+        size: 14.0,
+        content: content.to_string(),
+        font: 0,
+    };
+
+    let old = snapshot(&[quad(0.0, 100.0), text("Hello \"world\""), quad(0.0, 10.0)]);
+    assert_eq!(
+        old.lines().next().unwrap(),
+        "Quad position=0,0 size=100x50 z=1 color=#000000ff radius=0,0,0,0"
+    );
+    assert!(diff_snapshots(&old, &old).is_empty());
+
+    let new = snapshot(&[RenderPrimitive::Empty, quad(10.0, 120.5), text("Hello")]);
+    let diff = diff_snapshots(&old, &new);
+    assert_eq!(
+        diff.changes,
+        vec![
+            SnapshotChange::Moved {
+                index: 0,
+                kind: "Quad".to_string(),
+                from: "0,0".to_string(),
+                to: "10,0".to_string(),
+            },
+            SnapshotChange::Resized {
+                index: 0,
+                kind: "Quad".to_string(),
+                from: "100x50".to_string(),
+                to: "120.5x50".to_string(),
+            },
+            SnapshotChange::Changed {
+                index: 1,
+                kind: "Text".to_string(),
+                field: "content".to_string(),
+                from: "\"Hello \\\"world\\\"\"".to_string(),
+                to: "\"Hello\"".to_string(),
+            },
+            SnapshotChange::Removed {
+                index: 2,
+                line: "Quad position=0,0 size=10x50 z=1 color=#000000ff radius=0,0,0,0".to_string(),
+            },
+        ]
+    );
+}
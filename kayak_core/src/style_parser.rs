@@ -0,0 +1,685 @@
+use std::fmt;
+
+use morphorm::{LayoutType, PositionType, Units};
+
+use crate::{
+    background::{Background, ColorStop},
+    box_shadow::BoxShadow,
+    color::Color,
+    cursor::{CursorIcon, PointerEvents},
+    render_command::RenderCommand,
+    styles::Style,
+    stylesheet::{ClassStyle, StyleSheet},
+    transform::Transform,
+};
+
+/// An error found while parsing a style file
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleParseError {
+    /// The line the error is on, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for StyleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for StyleParseError {}
+
+/// A type that can be read from the value of a property in a style file
+pub trait ParseStyleValue: Sized {
+    fn parse_style_value(value: &str) -> Result<Self, String>;
+}
+
+impl ParseStyleValue for Units {
+    /// Parses `auto`, pixels (`10px` or `10`), percentages (`50%`) or stretch factors (`stretch(1)`)
+    fn parse_style_value(value: &str) -> Result<Self, String> {
+        if value == "auto" {
+            Ok(Units::Auto)
+        } else if let Some(percentage) = value.strip_suffix('%') {
+            parse_number(percentage).map(Units::Percentage)
+        } else if let Some(factor) = value
+            .strip_prefix("stretch(")
+            .and_then(|value| value.strip_suffix(')'))
+        {
+            parse_number(factor).map(Units::Stretch)
+        } else {
+            parse_pixels(value).map(Units::Pixels)
+        }
+    }
+}
+
+impl ParseStyleValue for f32 {
+    /// Parses a number (`0.5`) or a percentage (`50%`)
+    fn parse_style_value(value: &str) -> Result<Self, String> {
+        if value.ends_with('%') {
+            parse_percentage(value)
+        } else {
+            parse_number(value)
+        }
+    }
+}
+
+impl ParseStyleValue for Color {
+    /// Parses a hex color: `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`
+    fn parse_style_value(value: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid color `{}`, expected a hex color like `#1e90ff`",
+                value
+            )
+        };
+        let hex = value.strip_prefix('#').ok_or_else(invalid)?;
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let channel = |index: usize, width: usize| {
+            let digits = &hex[index * width..(index + 1) * width];
+            let channel = u8::from_str_radix(digits, 16).unwrap_or_default();
+            // Short colors repeat each digit (e.g., `f` is `ff`)
+            if width == 1 {
+                (channel * 17) as f32 / 255.0
+            } else {
+                channel as f32 / 255.0
+            }
+        };
+        match hex.len() {
+            3 => Ok(Color::new(channel(0, 1), channel(1, 1), channel(2, 1), 1.0)),
+            4 => Ok(Color::new(
+                channel(0, 1),
+                channel(1, 1),
+                channel(2, 1),
+                channel(3, 1),
+            )),
+            6 => Ok(Color::new(channel(0, 2), channel(1, 2), channel(2, 2), 1.0)),
+            8 => Ok(Color::new(
+                channel(0, 2),
+                channel(1, 2),
+                channel(2, 2),
+                channel(3, 2),
+            )),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl ParseStyleValue for (f32, f32, f32, f32) {
+    /// Parses one value used for all four corners or sides, or four values
+    fn parse_style_value(value: &str) -> Result<Self, String> {
+        let values = value
+            .split_whitespace()
+            .map(parse_pixels)
+            .collect::<Result<Vec<_>, _>>()?;
+        match values[..] {
+            [all] => Ok((all, all, all, all)),
+            [a, b, c, d] => Ok((a, b, c, d)),
+            _ => Err(format!("expected 1 or 4 values, found `{}`", value)),
+        }
+    }
+}
+
+impl ParseStyleValue for Background {
+    /// Parses a hex color, `linear-gradient(<angle>, <stops>)` or `radial-gradient(at <x>% <y>%, <stops>)`
+    ///
+    /// Like in CSS, the angle (in degrees like `90deg`, or a direction like `to right`) defaults to `to bottom` and
+    /// the center of radial gradients to `at 50% 50%`. Each stop is a color optionally followed by its position as
+    /// a percentage.
+    fn parse_style_value(value: &str) -> Result<Self, String> {
+        if let Some(arguments) = gradient_arguments(value, "linear-gradient") {
+            let mut arguments = arguments.split(',').map(str::trim).peekable();
+            let angle = match arguments.peek() {
+                Some(angle) if !angle.starts_with('#') => {
+                    let angle = parse_angle(angle)?;
+                    arguments.next();
+                    angle
+                }
+                _ => 180.0,
+            };
+            Ok(Background::LinearGradient {
+                angle,
+                stops: parse_color_stops(arguments)?,
+            })
+        } else if let Some(arguments) = gradient_arguments(value, "radial-gradient") {
+            let mut arguments = arguments.split(',').map(str::trim).peekable();
+            let center = match arguments
+                .peek()
+                .and_then(|center| center.strip_prefix("at "))
+            {
+                Some(center) => {
+                    let center = parse_center(center)?;
+                    arguments.next();
+                    center
+                }
+                None => (0.5, 0.5),
+            };
+            Ok(Background::RadialGradient {
+                center,
+                stops: parse_color_stops(arguments)?,
+            })
+        } else {
+            Color::parse_style_value(value).map(Background::Color)
+        }
+    }
+}
+
+impl ParseStyleValue for Option<BoxShadow> {
+    /// Parses `none` or a shadow like in CSS: `[inset] <offset-x> <offset-y> [<blur> [<spread>]] <color>`
+    fn parse_style_value(value: &str) -> Result<Self, String> {
+        if value == "none" {
+            return Ok(None);
+        }
+
+        let mut box_shadow = BoxShadow::default();
+        let mut color = None;
+        let mut lengths = Vec::new();
+        for part in value.split_whitespace() {
+            if part == "inset" {
+                box_shadow.inset = true;
+            } else if part.starts_with('#') {
+                color = Some(Color::parse_style_value(part)?);
+            } else {
+                lengths.push(parse_pixels(part)?);
+            }
+        }
+
+        box_shadow.color =
+            color.ok_or_else(|| format!("missing the color of the shadow in `{}`", value))?;
+        match lengths[..] {
+            [x, y] => box_shadow.offset = (x, y),
+            [x, y, blur] => {
+                box_shadow.offset = (x, y);
+                box_shadow.blur = blur;
+            }
+            [x, y, blur, spread] => {
+                box_shadow.offset = (x, y);
+                box_shadow.blur = blur;
+                box_shadow.spread = spread;
+            }
+            _ => return Err(format!("expected 2 to 4 lengths, found `{}`", value)),
+        }
+        Ok(Some(box_shadow))
+    }
+}
+
+impl ParseStyleValue for Transform {
+    /// Parses `none` or a list of functions: `translate(<x>, <y>)`, `scale(<factor>)` or `scale(<x>, <y>)`,
+    /// `rotate(<angle>deg)` and `origin(<x>% <y>%)`
+    ///
+    /// Unlike in CSS, the order of the functions doesn't matter since the widget is always scaled, rotated and then
+    /// translated. The origin (the CSS `transform-origin` property) defaults to the center of the widget.
+    fn parse_style_value(value: &str) -> Result<Self, String> {
+        let mut transform = Transform::default();
+        if value == "none" {
+            return Ok(transform);
+        }
+
+        let mut rest = value.trim();
+        while !rest.is_empty() {
+            let (function, remaining) = rest
+                .split_once(')')
+                .ok_or_else(|| format!("missing `)` in `{}`", value))?;
+            let (name, arguments) = function
+                .split_once('(')
+                .ok_or_else(|| format!("invalid transform function `{})`", function))?;
+            let arguments = arguments.split(',').map(str::trim).collect::<Vec<_>>();
+            match (name.trim(), &arguments[..]) {
+                ("translate", [x, y]) => {
+                    transform.translation = (parse_pixels(x)?, parse_pixels(y)?);
+                }
+                ("scale", [factor]) => {
+                    let factor = parse_number(factor)?;
+                    transform.scale = (factor, factor);
+                }
+                ("scale", [x, y]) => transform.scale = (parse_number(x)?, parse_number(y)?),
+                ("rotate", [angle]) => {
+                    transform.rotation = angle
+                        .strip_suffix("deg")
+                        .ok_or_else(|| {
+                            format!("invalid angle `{}`, expected degrees like `45deg`", angle)
+                        })
+                        .and_then(parse_number)?;
+                }
+                ("origin", [origin]) => {
+                    transform.origin = parse_center(origin).map_err(|_| {
+                        format!("expected an origin like `50% 50%`, found `{}`", origin)
+                    })?;
+                }
+                _ => {
+                    return Err(format!(
+                    "unknown transform function `{})`, expected translate, scale, rotate or origin",
+                    function.trim()
+                ))
+                }
+            }
+            rest = remaining.trim_start();
+        }
+        Ok(transform)
+    }
+}
+
+/// Implements [ParseStyleValue] for an enum from the keywords of its variants
+macro_rules! parse_keywords {
+    ($property_type:ty { $($keyword:literal => $variant:expr),* $(,)? }) => {
+        impl ParseStyleValue for $property_type {
+            fn parse_style_value(value: &str) -> Result<Self, String> {
+                match value {
+                    $($keyword => Ok($variant),)*
+                    _ => Err(format!(
+                        "unknown value `{}`, expected one of: {}",
+                        value,
+                        [$($keyword),*].join(", ")
+                    )),
+                }
+            }
+        }
+    };
+}
+
+parse_keywords!(LayoutType {
+    "row" => LayoutType::Row,
+    "column" => LayoutType::Column,
+});
+
+parse_keywords!(PositionType {
+    "self-directed" => PositionType::SelfDirected,
+    "parent-directed" => PositionType::ParentDirected,
+});
+
+// Commands holding data (e.g., text) can only be set by widgets
+parse_keywords!(RenderCommand {
+    "empty" => RenderCommand::Empty,
+    "layout" => RenderCommand::Layout,
+    "clip" => RenderCommand::Clip,
+    "quad" => RenderCommand::Quad,
+});
+
+parse_keywords!(PointerEvents {
+    "all" => PointerEvents::All,
+    "self-only" => PointerEvents::SelfOnly,
+    "children-only" => PointerEvents::ChildrenOnly,
+    "none" => PointerEvents::None,
+});
+
+parse_keywords!(CursorIcon {
+    "default" => CursorIcon::Default,
+    "pointer" => CursorIcon::Pointer,
+    "text" => CursorIcon::Text,
+    "grab" => CursorIcon::Grab,
+    "grabbing" => CursorIcon::Grabbing,
+    "move" => CursorIcon::Move,
+    "resize-horizontal" => CursorIcon::ResizeHorizontal,
+    "resize-vertical" => CursorIcon::ResizeVertical,
+    "resize-nwse" => CursorIcon::ResizeNwse,
+    "resize-nesw" => CursorIcon::ResizeNesw,
+    "not-allowed" => CursorIcon::NotAllowed,
+});
+
+fn parse_percentage(value: &str) -> Result<f32, String> {
+    let percentage = value
+        .strip_suffix('%')
+        .ok_or_else(|| format!("invalid percentage `{}`", value))?;
+    parse_number(percentage).map(|percentage| percentage / 100.0)
+}
+
+/// Returns what's between the parentheses of a gradient function with the given name
+fn gradient_arguments<'a>(value: &'a str, name: &str) -> Option<&'a str> {
+    value
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+fn parse_angle(value: &str) -> Result<f32, String> {
+    match value {
+        "to top" => Ok(0.0),
+        "to right" => Ok(90.0),
+        "to bottom" => Ok(180.0),
+        "to left" => Ok(270.0),
+        _ => value
+            .strip_suffix("deg")
+            .ok_or_else(|| {
+                format!(
+                    "invalid angle `{}`, expected degrees like `90deg` or a direction like `to right`",
+                    value
+                )
+            })
+            .and_then(parse_number),
+    }
+}
+
+fn parse_center(value: &str) -> Result<(f32, f32), String> {
+    match value.split_whitespace().collect::<Vec<_>>()[..] {
+        [x, y] => Ok((parse_percentage(x)?, parse_percentage(y)?)),
+        _ => Err(format!(
+            "expected a center like `at 50% 50%`, found `at {}`",
+            value
+        )),
+    }
+}
+
+/// Parses the color stops of a gradient, each being a color optionally followed by its position
+///
+/// Like in CSS, the first and last stops default to the start and the end of the gradient, the stops without a
+/// position are spread evenly between the ones around them, and a stop can't be before the previous one.
+fn parse_color_stops<'a>(stops: impl Iterator<Item = &'a str>) -> Result<Vec<ColorStop>, String> {
+    let mut colors = Vec::new();
+    let mut positions = Vec::new();
+    for stop in stops {
+        let mut parts = stop.split_whitespace();
+        colors.push(Color::parse_style_value(parts.next().unwrap_or_default())?);
+        positions.push(parts.next().map(parse_percentage).transpose()?);
+        if parts.next().is_some() {
+            return Err(format!("expected a color and a position, found `{}`", stop));
+        }
+    }
+    if colors.len() < 2 {
+        return Err(String::from("a gradient needs at least 2 colors"));
+    }
+
+    let last = positions.len() - 1;
+    positions[0].get_or_insert(0.0);
+    positions[last].get_or_insert(1.0);
+    let mut index = 1;
+    while index < last {
+        if positions[index].is_none() {
+            let start = index - 1;
+            let end = (index..=last).find(|&i| positions[i].is_some()).unwrap();
+            let (from, to) = (positions[start].unwrap(), positions[end].unwrap());
+            for (offset, position) in positions[index..end].iter_mut().enumerate() {
+                let step = (index + offset - start) as f32 / (end - start) as f32;
+                *position = Some(from + (to - from) * step);
+            }
+            index = end;
+        }
+        index += 1;
+    }
+
+    let mut previous = f32::MIN;
+    Ok(colors
+        .into_iter()
+        .zip(positions)
+        .map(|(color, position)| {
+            previous = position.unwrap().max(previous);
+            ColorStop::new(color, previous)
+        })
+        .collect())
+}
+
+fn parse_number(value: &str) -> Result<f32, String> {
+    value
+        .trim()
+        .parse::<f32>()
+        .map_err(|_| format!("invalid number `{}`", value))
+}
+
+fn parse_pixels(value: &str) -> Result<f32, String> {
+    parse_number(value.strip_suffix("px").unwrap_or(value))
+}
+
+/// Parses a stylesheet from a CSS-like file
+///
+/// Each block styles a class, optionally followed by the state it applies in (`:hover`, `:active`, `:focus` or
+/// `:disabled`). Properties use the names of the [Style] fields with dashes instead of underscores (see
+/// [Style::set_property]):
+///
+/// ```text
+/// /* Built-in buttons */
+/// button {
+///     background-color: #141719;
+///     border-radius: 5px;
+///     height: 45px;
+///     padding-left: stretch(1);
+///     padding-right: stretch(1);
+/// }
+///
+/// button:hover {
+///     background-color: #1f2327;
+/// }
+/// ```
+///
+/// Blocks for the same class and state are combined, later properties taking precedence. Comments are written
+/// between `/*` and `*/`.
+pub fn parse_stylesheet(source: &str) -> Result<StyleSheet, StyleParseError> {
+    let source = strip_comments(source);
+    let line_at = |offset: usize| source[..offset].matches('\n').count() + 1;
+    let error = |offset: usize, message: String| StyleParseError {
+        line: line_at(offset),
+        message,
+    };
+
+    let mut stylesheet = StyleSheet::new();
+    let mut offset = 0;
+    while let Some(block_start) = source[offset..].find('{').map(|start| offset + start) {
+        let selector = source[offset..block_start].trim();
+        let block_end = source[block_start..]
+            .find('}')
+            .map(|end| block_start + end)
+            .ok_or_else(|| error(block_start, format!("unclosed block for `{}`", selector)))?;
+
+        let (class, state) = match selector.split_once(':') {
+            Some((class, state)) => (class.trim(), Some(state.trim())),
+            None => (selector, None),
+        };
+        if class.is_empty() || class.contains(char::is_whitespace) {
+            return Err(error(
+                block_start,
+                format!("invalid selector `{}`", selector),
+            ));
+        }
+
+        if stylesheet.get_class(class).is_none() {
+            stylesheet.set_class(class, ClassStyle::default());
+        }
+        let class_style = stylesheet.get_class_mut(class).unwrap();
+        let styles = match state {
+            None => &mut class_style.base,
+            Some("hover") => class_style.hover.get_or_insert_with(Style::default),
+            Some("active") => class_style.active.get_or_insert_with(Style::default),
+            Some("focus") => class_style.focused.get_or_insert_with(Style::default),
+            Some("disabled") => class_style.disabled.get_or_insert_with(Style::default),
+            Some(state) => {
+                return Err(error(
+                    block_start,
+                    format!(
+                        "unknown state `{}`, expected one of: hover, active, focus, disabled",
+                        state
+                    ),
+                ))
+            }
+        };
+
+        let mut declaration_start = block_start + 1;
+        for declaration in source[block_start + 1..block_end].split(';') {
+            let declaration_offset =
+                declaration_start + (declaration.len() - declaration.trim_start().len());
+            declaration_start += declaration.len() + 1;
+            if declaration.trim().is_empty() {
+                continue;
+            }
+
+            let (name, value) = declaration.split_once(':').ok_or_else(|| {
+                error(
+                    declaration_offset,
+                    format!("expected `name: value`, found `{}`", declaration.trim()),
+                )
+            })?;
+            styles
+                .set_property(name.trim(), value.trim())
+                .map_err(|message| error(declaration_offset, message))?;
+        }
+
+        offset = block_end + 1;
+    }
+
+    if !source[offset..].trim().is_empty() {
+        return Err(error(offset, String::from("expected a block")));
+    }
+
+    Ok(stylesheet)
+}
+
+/// Replaces `/* ... */` comments with spaces, keeping line breaks so that line numbers are preserved
+fn strip_comments(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        stripped.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("*/")
+            .map_or(rest.len(), |end| start + end + 2);
+        stripped.extend(
+            rest[start..end]
+                .chars()
+                .map(|c| if c == '\n' { '\n' } else { ' ' }),
+        );
+        rest = &rest[end..];
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+#[test]
+fn test_parse_stylesheet() {
+    use crate::styles::StyleProp;
+    use crate::stylesheet::StyleState;
+
+    let stylesheet = parse_stylesheet(
+        "
+        /* A button */
+        button {
+            background-color: #ff0000;
+            border-radius: 5px 0 0 5px;
+            border: 1px 0 2px 0;
+            border-color: #fff;
+            box-shadow: 0 2px 8px #00000080;
+            height: 45px;
+            width: 50%;
+            padding-left: stretch(1);
+            top: auto;
+            color: inherit;
+            opacity: 80%;
+            transform: scale(0.95) rotate(-2deg) origin(50% 100%);
+            cursor: pointer
+        }
+
+        button:hover {
+            background-color: #00f;
+        }
+        ",
+    )
+    .unwrap();
+
+    let button = stylesheet.get_class("button").unwrap();
+    assert_eq!(
+        button.base.background_color,
+        StyleProp::Value(Color::new(1.0, 0.0, 0.0, 1.0))
+    );
+    assert_eq!(
+        button.base.border_radius,
+        StyleProp::Value((5.0, 0.0, 0.0, 5.0))
+    );
+    assert_eq!(button.base.border, StyleProp::Value((1.0, 0.0, 2.0, 0.0)));
+    assert_eq!(button.base.border_color, StyleProp::Value(Color::WHITE));
+    assert_eq!(
+        button.base.box_shadow,
+        StyleProp::Value(Some(BoxShadow {
+            offset: (0.0, 2.0),
+            blur: 8.0,
+            color: Color::new(0.0, 0.0, 0.0, 128.0 / 255.0),
+            ..BoxShadow::default()
+        }))
+    );
+    assert_eq!(button.base.height, StyleProp::Value(Units::Pixels(45.0)));
+    assert_eq!(button.base.width, StyleProp::Value(Units::Percentage(50.0)));
+    assert_eq!(
+        button.base.padding_left,
+        StyleProp::Value(Units::Stretch(1.0))
+    );
+    assert_eq!(button.base.top, StyleProp::Value(Units::Auto));
+    assert_eq!(button.base.color, StyleProp::Inherit);
+    assert_eq!(button.base.opacity, StyleProp::Value(0.8));
+    assert_eq!(
+        button.base.transform,
+        StyleProp::Value(Transform {
+            scale: (0.95, 0.95),
+            rotation: -2.0,
+            origin: (0.5, 1.0),
+            ..Transform::default()
+        })
+    );
+    assert_eq!(button.base.cursor, StyleProp::Value(CursorIcon::Pointer));
+
+    let hovered = stylesheet
+        .resolve(
+            "button",
+            StyleState {
+                hovered: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        hovered.background_color,
+        StyleProp::Value(Color::new(0.0, 0.0, 1.0, 1.0))
+    );
+}
+
+#[test]
+fn test_parse_gradients() {
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let green = Color::new(0.0, 1.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+
+    assert_eq!(
+        Background::parse_style_value("linear-gradient(to right, #f00, #0f0, #00f 80%)"),
+        Ok(Background::LinearGradient {
+            angle: 90.0,
+            stops: vec![
+                ColorStop::new(red, 0.0),
+                ColorStop::new(green, 0.4),
+                ColorStop::new(blue, 0.8),
+            ],
+        })
+    );
+    assert_eq!(
+        Background::parse_style_value("linear-gradient(#f00 50%, #00f 20%)"),
+        Ok(Background::LinearGradient {
+            angle: 180.0,
+            stops: vec![ColorStop::new(red, 0.5), ColorStop::new(blue, 0.5)],
+        })
+    );
+    assert_eq!(
+        Background::parse_style_value("radial-gradient(at 25% 0%, #f00, #00f)"),
+        Ok(Background::RadialGradient {
+            center: (0.25, 0.0),
+            stops: vec![ColorStop::new(red, 0.0), ColorStop::new(blue, 1.0)],
+        })
+    );
+    assert_eq!(
+        Background::parse_style_value("#f00"),
+        Ok(Background::Color(red))
+    );
+
+    assert!(Background::parse_style_value("linear-gradient(45deg, #f00)").is_err());
+    assert!(Background::parse_style_value("linear-gradient(sideways, #f00, #00f)").is_err());
+}
+
+#[test]
+fn test_parse_stylesheet_errors() {
+    let error = parse_stylesheet("button {\n    height: 45px;\n    colour: #fff;\n}").unwrap_err();
+    assert_eq!(error.line, 3);
+
+    let error = parse_stylesheet("button {\n    height: tall;\n}").unwrap_err();
+    assert_eq!(error.line, 2);
+
+    assert!(parse_stylesheet("button:pressed { }").is_err());
+    assert!(parse_stylesheet("button { box-shadow: 0 2px 8px; }").is_err());
+    assert!(parse_stylesheet("button { height: 45px;").is_err());
+    assert!(parse_stylesheet("button { transform: skew(10deg); }").is_err());
+    assert!(parse_stylesheet("button { transform: rotate(45); }").is_err());
+}
@@ -1,7 +1,10 @@
 pub use morphorm::{LayoutType, PositionType, Units};
 
-use crate::{color::Color, render_command::RenderCommand};
-use crate::cursor::PointerEvents;
+use crate::cursor::{CursorIcon, PointerEvents};
+use crate::{
+    background::Background, box_shadow::BoxShadow, color::Color, render_command::RenderCommand,
+    style_parser::ParseStyleValue, transform::Transform,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum StyleProp<T: Default + Clone> {
@@ -32,172 +35,183 @@ where
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct Style {
-    pub background_color: StyleProp<Color>,
-    pub border_radius: StyleProp<(f32, f32, f32, f32)>,
-    pub bottom: StyleProp<Units>,
-    pub color: StyleProp<Color>,
-    pub height: StyleProp<Units>,
-    pub layout_type: StyleProp<LayoutType>,
-    pub left: StyleProp<Units>,
-    pub position_type: StyleProp<PositionType>,
-    pub render_command: StyleProp<RenderCommand>,
-    pub right: StyleProp<Units>,
-    pub top: StyleProp<Units>,
-    pub width: StyleProp<Units>,
-    pub padding_left: StyleProp<Units>,
-    pub padding_right: StyleProp<Units>,
-    pub padding_top: StyleProp<Units>,
-    pub padding_bottom: StyleProp<Units>,
-    pub margin_left: StyleProp<Units>,
-    pub margin_right: StyleProp<Units>,
-    pub margin_top: StyleProp<Units>,
-    pub margin_bottom: StyleProp<Units>,
-    pub min_width: StyleProp<Units>,
-    pub min_height: StyleProp<Units>,
-    pub max_width: StyleProp<Units>,
-    pub max_height: StyleProp<Units>,
-    pub pointer_events: StyleProp<PointerEvents>
-}
-
-impl Default for Style {
-    fn default() -> Self {
-        Self {
-            background_color: StyleProp::Default,
-            border_radius: StyleProp::Default,
-            render_command: StyleProp::Value(RenderCommand::Empty),
-            bottom: StyleProp::Default,
-            color: StyleProp::Inherit,
-            height: StyleProp::Default,
-            layout_type: StyleProp::Default,
-            left: StyleProp::Default,
-            position_type: StyleProp::Default,
-            right: StyleProp::Default,
-            top: StyleProp::Default,
-            width: StyleProp::Default,
-            padding_left: StyleProp::Default,
-            padding_right: StyleProp::Default,
-            padding_top: StyleProp::Default,
-            padding_bottom: StyleProp::Default,
-            margin_left: StyleProp::Default,
-            margin_right: StyleProp::Default,
-            margin_top: StyleProp::Default,
-            margin_bottom: StyleProp::Default,
-            min_width: StyleProp::Default,
-            min_height: StyleProp::Default,
-            max_width: StyleProp::Default,
-            max_height: StyleProp::Default,
-            pointer_events: StyleProp::Default,
+/// Defines [Style] from a single table of properties
+///
+/// Each property is declared with its type and default value, from which the struct, its [Default] implementation
+/// and the methods going through every property ([Style::merge], [Style::apply] and [Style::set_property]) are
+/// generated. Like in CSS, some properties are inherited from the parent by default (e.g.,
+/// `color`), which is declared with a default of `StyleProp::Inherit`. The others (e.g., sizes) aren't.
+macro_rules! define_styles {
+    ($($(#[$attribute:meta])* $name:ident: $property_type:ty = $default:expr),* $(,)?) => {
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Style {
+            $(
+                $(#[$attribute])*
+                pub $name: StyleProp<$property_type>,
+            )*
         }
-    }
-}
 
-impl Style {
-    pub fn merge(&mut self, other: &Self) {
-        match self.background_color {
-            StyleProp::Inherit => {
-                self.background_color = other.background_color.clone();
-            }
-            _ => (),
-        }
-        match self.border_radius {
-            StyleProp::Inherit => {
-                self.border_radius = other.border_radius.clone();
-            }
-            _ => (),
-        }
-        match self.bottom {
-            StyleProp::Inherit => {
-                self.bottom = other.bottom.clone();
-            }
-            _ => (),
-        }
-        match self.color {
-            StyleProp::Inherit => {
-                self.color = other.color.clone();
+        impl Default for Style {
+            fn default() -> Self {
+                Self {
+                    $($name: $default,)*
+                }
             }
-            _ => (),
         }
-        match self.height {
-            StyleProp::Inherit => {
-                self.height = other.height.clone();
-            }
-            _ => (),
-        }
-        match self.layout_type {
-            StyleProp::Inherit => {
-                self.layout_type = other.layout_type.clone();
-            }
-            _ => (),
-        }
-        match self.left {
-            StyleProp::Inherit => {
-                self.left = other.left.clone();
-            }
-            _ => (),
-        }
-        match self.position_type {
-            StyleProp::Inherit => {
-                self.position_type = other.position_type.clone();
-            }
-            _ => (),
-        }
-        match self.render_command {
-            StyleProp::Inherit => {
-                self.render_command = other.render_command.clone();
+
+        impl Style {
+            /// Returns styles with every property set to [StyleProp::Default]
+            ///
+            /// These are the styles inherited by the root widget, so that inherited properties set nowhere resolve
+            /// to their default value.
+            pub fn initial() -> Self {
+                Self {
+                    $($name: StyleProp::Default,)*
+                }
             }
-            _ => (),
-        }
-        match self.right {
-            StyleProp::Inherit => {
-                self.right = other.right.clone();
+
+            /// Replaces every property set to [StyleProp::Inherit] with the one of `other` (the parent's styles)
+            pub fn merge(&mut self, other: &Self) {
+                $(
+                    if matches!(self.$name, StyleProp::Inherit) {
+                        self.$name = other.$name.clone();
+                    }
+                )*
             }
-            _ => (),
-        }
-        match self.top {
-            StyleProp::Inherit => {
-                self.top = other.top.clone();
+
+            /// Overrides the properties of this style with the ones set in `other`
+            ///
+            /// Properties of `other` left to their default value don't override anything, which lets styles be
+            /// layered on top of each other (e.g., a widget's `styles` on top of its [class](crate::StyleSheet)).
+            ///
+            /// This means a property can't be reset by setting it to its default value, since that can't be told
+            /// apart from leaving it unset: [StyleProp::Default] (and [StyleProp::Inherit] for the inherited
+            /// properties, e.g. `color`) is ignored, as are `initial` and `inherit` in a style file. To override a
+            /// class, set the value to use explicitly instead (e.g., `StyleProp::Value(Units::Auto)`).
+            pub fn apply(&mut self, other: &Self) {
+                $(
+                    if other.$name != $default {
+                        self.$name = other.$name.clone();
+                    }
+                )*
             }
-            _ => (),
-        }
-        match self.width {
-            StyleProp::Inherit => {
-                self.width = other.width.clone();
+
+            /// Sets a property from its name and value as written in a style file (see
+            /// [parse_stylesheet](crate::parse_stylesheet))
+            ///
+            /// Names are the ones of the fields with dashes instead of underscores (e.g., `background-color`). Besides
+            /// the values of the property's type, `inherit` sets it to [StyleProp::Inherit] and `initial` to
+            /// [StyleProp::Default].
+            pub fn set_property(&mut self, name: &str, value: &str) -> Result<(), String> {
+                $(
+                    if name.replace('-', "_") == stringify!($name) {
+                        self.$name = match value {
+                            "inherit" => StyleProp::Inherit,
+                            "initial" => StyleProp::Default,
+                            value => StyleProp::Value(
+                                <$property_type as ParseStyleValue>::parse_style_value(value)
+                                    .map_err(|message| format!("{}: {}", name, message))?,
+                            ),
+                        };
+                        return Ok(());
+                    }
+                )*
+                Err(format!("unknown property `{}`", name))
             }
-            _ => (),
-        }
-        match self.padding_left {
-            StyleProp::Inherit => self.padding_left = other.padding_left.clone(),
-            _ => (),
-        }
-        match self.padding_right {
-            StyleProp::Inherit => self.padding_right = other.padding_right.clone(),
-            _ => (),
-        }
-        match self.padding_top {
-            StyleProp::Inherit => self.padding_top = other.padding_top.clone(),
-            _ => (),
-        }
-        match self.padding_bottom {
-            StyleProp::Inherit => self.padding_bottom = other.padding_bottom.clone(),
-            _ => (),
-        }
-        match self.margin_left {
-            StyleProp::Inherit => self.margin_left = other.margin_left.clone(),
-            _ => (),
-        }
-        match self.margin_right {
-            StyleProp::Inherit => self.margin_right = other.margin_right.clone(),
-            _ => (),
-        }
-        match self.margin_top {
-            StyleProp::Inherit => self.margin_top = other.margin_top.clone(),
-            _ => (),
         }
-        match self.margin_bottom {
-            StyleProp::Inherit => self.margin_bottom = other.margin_bottom.clone(),
-            _ => (),
-        }
-    }
+    };
+}
+
+define_styles! {
+    /// A color or a gradient, drawn instead of `background_color` when set
+    background: Background = StyleProp::Default,
+    background_color: Color = StyleProp::Default,
+    border_radius: (f32, f32, f32, f32) = StyleProp::Default,
+    /// The width of the border on each side: top, right, bottom and left
+    ///
+    /// Borders are drawn inside the widget and don't affect its layout.
+    border: (f32, f32, f32, f32) = StyleProp::Default,
+    border_color: Color = StyleProp::Default,
+    /// Only drawn for quads
+    box_shadow: Option<BoxShadow> = StyleProp::Default,
+    bottom: Units = StyleProp::Default,
+    color: Color = StyleProp::Inherit,
+    height: Units = StyleProp::Default,
+    layout_type: LayoutType = StyleProp::Default,
+    left: Units = StyleProp::Default,
+    position_type: PositionType = StyleProp::Default,
+    render_command: RenderCommand = StyleProp::Value(RenderCommand::Empty),
+    right: Units = StyleProp::Default,
+    top: Units = StyleProp::Default,
+    width: Units = StyleProp::Default,
+    padding_left: Units = StyleProp::Default,
+    padding_right: Units = StyleProp::Default,
+    padding_top: Units = StyleProp::Default,
+    padding_bottom: Units = StyleProp::Default,
+    margin_left: Units = StyleProp::Default,
+    margin_right: Units = StyleProp::Default,
+    margin_top: Units = StyleProp::Default,
+    margin_bottom: Units = StyleProp::Default,
+    min_width: Units = StyleProp::Default,
+    min_height: Units = StyleProp::Default,
+    max_width: Units = StyleProp::Default,
+    max_height: Units = StyleProp::Default,
+    /// Not inherited by default since it already applies to the children of a widget (see [PointerEvents])
+    pointer_events: PointerEvents = StyleProp::Default,
+    /// From 0.0 (invisible) to 1.0 (opaque, used when not set), multiplied with the opacity of the parent widgets
+    ///
+    /// Not inherited since it already applies to the children of a widget: fading a widget fades all of them.
+    opacity: f32 = StyleProp::Default,
+    /// Moves, scales and rotates the widget and its children after layout, when drawing and hit-testing them
+    ///
+    /// Not inherited since it already applies to the children of a widget: their transforms are applied on top.
+    transform: Transform = StyleProp::Default,
+    /// The cursor icon shown over this widget
+    cursor: CursorIcon = StyleProp::Inherit,
+}
+
+#[test]
+fn test_merge_every_property() {
+    let parent = Style {
+        color: StyleProp::Value(Color::WHITE),
+        min_width: StyleProp::Value(Units::Pixels(10.0)),
+        pointer_events: StyleProp::Value(PointerEvents::None),
+        ..Style::initial()
+    };
+    let mut child = Style {
+        min_width: StyleProp::Inherit,
+        pointer_events: StyleProp::Inherit,
+        ..Style::default()
+    };
+    child.merge(&parent);
+
+    assert_eq!(child.color, StyleProp::Value(Color::WHITE));
+    assert_eq!(child.min_width, StyleProp::Value(Units::Pixels(10.0)));
+    assert_eq!(child.pointer_events, StyleProp::Value(PointerEvents::None));
+    // Not inherited by default
+    assert_eq!(child.width, StyleProp::Default);
+}
+
+#[test]
+fn test_apply_only_overrides_set_properties() {
+    let mut base = Style {
+        background_color: StyleProp::Value(Color::WHITE),
+        width: StyleProp::Value(Units::Pixels(10.0)),
+        ..Style::default()
+    };
+    base.apply(&Style {
+        width: StyleProp::Value(Units::Pixels(20.0)),
+        ..Style::default()
+    });
+
+    assert_eq!(base.background_color, StyleProp::Value(Color::WHITE));
+    assert_eq!(base.width, StyleProp::Value(Units::Pixels(20.0)));
+
+    // Default values can't reset a property
+    base.apply(&Style {
+        background_color: StyleProp::Default,
+        color: StyleProp::Inherit,
+        ..Style::default()
+    });
+    assert_eq!(base.background_color, StyleProp::Value(Color::WHITE));
 }
@@ -0,0 +1,330 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{styles::Style, Index};
+
+/// The states a widget can be in, each of which can be styled by a [ClassStyle] variant
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StyleState {
+    /// The mouse is over the widget or one of its descendants
+    pub hovered: bool,
+    /// The mouse was pressed or a finger is touching the widget or one of its descendants
+    pub active: bool,
+    /// The widget has focus
+    pub focused: bool,
+    /// The widget is [disabled](crate::Widget::disabled)
+    pub disabled: bool,
+}
+
+/// The widgets the user is interacting with, from which the [StyleState] of every widget is derived
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Interaction {
+    /// The topmost widget under the mouse
+    pub hovered: Option<Index>,
+    /// The topmost widget the mouse was pressed on
+    pub active: Option<Index>,
+    /// The widgets the fingers touching the screen started on, sorted
+    pub touched: Vec<Index>,
+    pub focused: Option<Index>,
+}
+
+/// The styles of a class, along with the variants applied on top of them depending on the widget's [StyleState]
+///
+/// Like the styles passed to widgets, each style only needs to set the properties it changes:
+///
+/// ```ignore
+/// let button = ClassStyle::new(Style {
+///     background_color: StyleProp::Value(Color::new(0.0781, 0.0898, 0.101, 1.0)),
+///     ..Style::default()
+/// })
+/// .with_hover(Style {
+///     background_color: StyleProp::Value(Color::new(0.15, 0.16, 0.17, 1.0)),
+///     ..Style::default()
+/// });
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClassStyle {
+    pub base: Style,
+    pub hover: Option<Style>,
+    pub active: Option<Style>,
+    pub focused: Option<Style>,
+    pub disabled: Option<Style>,
+}
+
+impl ClassStyle {
+    pub fn new(base: Style) -> Self {
+        Self {
+            base,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_hover(mut self, hover: Style) -> Self {
+        self.hover = Some(hover);
+        self
+    }
+
+    pub fn with_active(mut self, active: Style) -> Self {
+        self.active = Some(active);
+        self
+    }
+
+    pub fn with_focused(mut self, focused: Style) -> Self {
+        self.focused = Some(focused);
+        self
+    }
+
+    pub fn with_disabled(mut self, disabled: Style) -> Self {
+        self.disabled = Some(disabled);
+        self
+    }
+
+    /// Applies the styles and variants of another class on top of these ones, see [Style::apply]
+    pub fn apply(&mut self, other: &ClassStyle) {
+        self.base.apply(&other.base);
+        for (variant, other) in [
+            (&mut self.hover, &other.hover),
+            (&mut self.active, &other.active),
+            (&mut self.focused, &other.focused),
+            (&mut self.disabled, &other.disabled),
+        ] {
+            match (variant.as_mut(), other) {
+                (Some(variant), Some(other)) => variant.apply(other),
+                (None, Some(other)) => *variant = Some(other.clone()),
+                (_, None) => {}
+            }
+        }
+    }
+
+    /// Returns whether the styles depend on the state of the widget
+    pub fn has_variants(&self) -> bool {
+        self.hover.is_some()
+            || self.active.is_some()
+            || self.focused.is_some()
+            || self.disabled.is_some()
+    }
+
+    /// Returns the base styles with the variants matching the given state applied on top of them
+    ///
+    /// Variants are applied in the following order, the last one taking precedence: hover, focused, active,
+    /// disabled.
+    pub fn resolve(&self, state: StyleState) -> Style {
+        let mut styles = self.base.clone();
+        let variants = [
+            (state.hovered, &self.hover),
+            (state.focused, &self.focused),
+            (state.active, &self.active),
+            (state.disabled, &self.disabled),
+        ];
+        for (is_set, variant) in variants {
+            if let (true, Some(variant)) = (is_set, variant) {
+                styles.apply(variant);
+            }
+        }
+        styles
+    }
+}
+
+/// A set of named classes that widgets opt into with their `class` prop
+///
+/// A widget's class is a list of class names separated by spaces, applied in order. The resulting styles are
+/// layered under the widget's own `styles`, which means styles passed inline always win over the stylesheet.
+/// Classes are resolved by the [WidgetManager](crate::widget_manager::WidgetManager) when it builds the nodes,
+/// so state variants (e.g., hover) update without rendering the widget again.
+///
+/// ```ignore
+/// context.set_stylesheet(StyleSheet::new().with_class("danger", ClassStyle::new(Style {
+///     background_color: StyleProp::Value(Color::new(0.8, 0.1, 0.1, 1.0)),
+///     ..Style::default()
+/// })));
+///
+/// rsx! {
+///     <Button class={Some("danger".to_string())}>...</Button>
+/// }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StyleSheet {
+    classes: HashMap<String, ClassStyle>,
+}
+
+impl StyleSheet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_class<S: Into<String>>(mut self, name: S, class: ClassStyle) -> Self {
+        self.set_class(name, class);
+        self
+    }
+
+    /// Adds a class, replacing the one with the same name if any
+    pub fn set_class<S: Into<String>>(&mut self, name: S, class: ClassStyle) {
+        self.classes.insert(name.into(), class);
+    }
+
+    pub fn get_class(&self, name: &str) -> Option<&ClassStyle> {
+        self.classes.get(name)
+    }
+
+    pub fn get_class_mut(&mut self, name: &str) -> Option<&mut ClassStyle> {
+        self.classes.get_mut(name)
+    }
+
+    pub fn remove_class(&mut self, name: &str) -> Option<ClassStyle> {
+        self.classes.remove(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Adds the classes of another stylesheet, replacing the ones with the same name
+    pub fn extend(&mut self, other: StyleSheet) {
+        self.classes.extend(other.classes);
+    }
+
+    /// Applies the classes of another stylesheet on top of the ones with the same name, adding the others
+    pub fn apply(&mut self, other: &StyleSheet) {
+        for (name, class) in &other.classes {
+            match self.classes.get_mut(name) {
+                Some(own_class) => own_class.apply(class),
+                None => {
+                    self.classes.insert(name.clone(), class.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns the names of the classes that differ between both stylesheets
+    pub(crate) fn changed_classes(&self, other: &StyleSheet) -> HashSet<String> {
+        self.classes
+            .keys()
+            .chain(other.classes.keys())
+            .filter(|name| self.classes.get(*name) != other.classes.get(*name))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns whether any class depends on the state of the widgets
+    pub fn has_variants(&self) -> bool {
+        self.classes.values().any(ClassStyle::has_variants)
+    }
+
+    /// Resolves a space separated list of classes for a widget in the given state
+    ///
+    /// Returns `None` if none of the classes exist.
+    pub fn resolve(&self, class: &str, state: StyleState) -> Option<Style> {
+        let mut styles: Option<Style> = None;
+        for class_style in class
+            .split_whitespace()
+            .filter_map(|name| self.classes.get(name))
+        {
+            styles
+                .get_or_insert_with(Style::default)
+                .apply(&class_style.resolve(state));
+        }
+        styles
+    }
+}
+
+#[test]
+fn test_resolve_classes_and_variants() {
+    use crate::{
+        color::Color,
+        styles::{StyleProp, Units},
+    };
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+    let stylesheet = StyleSheet::new()
+        .with_class(
+            "button",
+            ClassStyle::new(Style {
+                background_color: StyleProp::Value(Color::WHITE),
+                height: StyleProp::Value(Units::Pixels(45.0)),
+                ..Style::default()
+            })
+            .with_hover(Style {
+                background_color: StyleProp::Value(red),
+                ..Style::default()
+            })
+            .with_disabled(Style {
+                background_color: StyleProp::Value(blue),
+                ..Style::default()
+            }),
+        )
+        .with_class(
+            "tall",
+            ClassStyle::new(Style {
+                height: StyleProp::Value(Units::Pixels(90.0)),
+                ..Style::default()
+            }),
+        );
+
+    let styles = stylesheet
+        .resolve("button tall", StyleState::default())
+        .unwrap();
+    assert_eq!(styles.background_color, StyleProp::Value(Color::WHITE));
+    assert_eq!(styles.height, StyleProp::Value(Units::Pixels(90.0)));
+
+    let hovered = StyleState {
+        hovered: true,
+        ..Default::default()
+    };
+    let styles = stylesheet.resolve("button", hovered).unwrap();
+    assert_eq!(styles.background_color, StyleProp::Value(red));
+
+    // Disabled takes precedence over hover
+    let styles = stylesheet
+        .resolve(
+            "button",
+            StyleState {
+                disabled: true,
+                ..hovered
+            },
+        )
+        .unwrap();
+    assert_eq!(styles.background_color, StyleProp::Value(blue));
+
+    assert!(stylesheet
+        .resolve("unknown", StyleState::default())
+        .is_none());
+}
+
+#[test]
+fn test_apply_stylesheet_over_defaults() {
+    use crate::{
+        color::Color,
+        styles::{StyleProp, Units},
+    };
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let mut stylesheet = StyleSheet::new().with_class(
+        "button",
+        ClassStyle::new(Style {
+            background_color: StyleProp::Value(Color::WHITE),
+            height: StyleProp::Value(Units::Pixels(45.0)),
+            ..Style::default()
+        }),
+    );
+    stylesheet.apply(
+        &StyleSheet::new()
+            .with_class(
+                "button",
+                ClassStyle::new(Style {
+                    height: StyleProp::Value(Units::Pixels(30.0)),
+                    ..Style::default()
+                })
+                .with_hover(Style {
+                    background_color: StyleProp::Value(red),
+                    ..Style::default()
+                }),
+            )
+            .with_class("danger", ClassStyle::default()),
+    );
+
+    let button = stylesheet.get_class("button").unwrap();
+    assert_eq!(button.base.background_color, StyleProp::Value(Color::WHITE));
+    assert_eq!(button.base.height, StyleProp::Value(Units::Pixels(30.0)));
+    assert!(button.hover.is_some());
+    assert!(stylesheet.get_class("danger").is_some());
+}
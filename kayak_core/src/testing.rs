@@ -0,0 +1,239 @@
+use std::time::{Duration, Instant};
+
+use as_any::Downcast;
+
+use crate::{
+    context::KayakContext,
+    layout_cache::Rect,
+    render_primitive::RenderPrimitive,
+    snapshot,
+    styles::{StyleProp, Units},
+    Index, InputEvent, KeyCode, Widget,
+};
+
+/// A headless [KayakContext] used to test widgets without a renderer
+///
+/// The root widget is laid out to fill a fixed viewport. Input is simulated one frame at a time: every call
+/// that sends input processes the events and then renders, just like a renderer would between frames. Time is
+/// simulated as well and only passes with [advance_time](Self::advance_time).
+///
+/// ```ignore
+/// let mut ui = TestContext::new((800.0, 600.0), |context| {
+///     render! {
+///         <Element>
+///             <Button on_event={Some(on_event)} />
+///         </Element>
+///     }
+/// });
+///
+/// let button = ui.find_widget::<Button>().unwrap();
+/// ui.click_widget(button);
+/// ```
+pub struct TestContext {
+    pub kayak_context: KayakContext,
+    viewport: (f32, f32),
+    time: Instant,
+}
+
+impl TestContext {
+    /// Creates the context, builds the widget tree with `f` and renders it once
+    pub fn new<F: FnOnce(&mut KayakContext)>(viewport: (f32, f32), f: F) -> Self {
+        let mut kayak_context = KayakContext::new();
+        f(&mut kayak_context);
+        kayak_context.widget_manager.dirty(true);
+
+        let mut test_context = Self {
+            kayak_context,
+            viewport,
+            time: Instant::now(),
+        };
+        test_context.render();
+        test_context
+    }
+
+    pub fn viewport(&self) -> (f32, f32) {
+        self.viewport
+    }
+
+    /// Resizes the viewport and lays out the tree again
+    pub fn set_viewport(&mut self, viewport: (f32, f32)) {
+        self.viewport = viewport;
+        self.render();
+    }
+
+    /// Renders all dirty widgets and calculates the layout within the viewport
+    pub fn render(&mut self) {
+        self.kayak_context.render();
+
+        // The root node is sized to the viewport, like the `App` widget does with the window size
+        let widget_manager = &mut self.kayak_context.widget_manager;
+        if let Some(root) = widget_manager.node_tree.root_node {
+            if let Some(Some(node)) = widget_manager.nodes.get_mut(root) {
+                node.styles.width = StyleProp::Value(Units::Pixels(self.viewport.0));
+                node.styles.height = StyleProp::Value(Units::Pixels(self.viewport.1));
+            }
+        }
+        widget_manager.calculate_layout();
+    }
+
+    /// Processes the given input events as a single frame, then renders
+    pub fn process_events(&mut self, input_events: Vec<InputEvent>) {
+        self.kayak_context
+            .process_events_at(input_events, self.time);
+        self.render();
+    }
+
+    /// Lets the given amount of simulated time pass, then processes a frame without input
+    pub fn advance_time(&mut self, duration: Duration) {
+        self.time += duration;
+        self.process_events(Vec::new());
+    }
+
+    pub fn move_mouse(&mut self, position: (f32, f32)) {
+        self.process_events(vec![InputEvent::MouseMoved(position)]);
+    }
+
+    /// Moves the mouse to `position`, then presses and releases the left button over three frames
+    pub fn click(&mut self, position: (f32, f32)) {
+        self.move_mouse(position);
+        self.process_events(vec![InputEvent::MouseLeftPress]);
+        self.process_events(vec![InputEvent::MouseLeftRelease]);
+    }
+
+    /// Presses the left button at `from`, moves the mouse to `to` and releases it, over four frames
+    pub fn drag(&mut self, from: (f32, f32), to: (f32, f32)) {
+        self.move_mouse(from);
+        self.process_events(vec![InputEvent::MouseLeftPress]);
+        self.move_mouse(to);
+        self.process_events(vec![InputEvent::MouseLeftRelease]);
+    }
+
+    /// Touches the screen at `position` and lifts the finger in the next frame
+    pub fn tap(&mut self, position: (f32, f32)) {
+        self.process_events(vec![InputEvent::TouchStart { id: 0, position }]);
+        self.process_events(vec![InputEvent::TouchEnd { id: 0, position }]);
+    }
+
+    /// Moves the mouse to `position`, then presses and releases the right button over three frames
+    pub fn right_click(&mut self, position: (f32, f32)) {
+        self.move_mouse(position);
+        self.process_events(vec![InputEvent::MouseRightPress]);
+        self.process_events(vec![InputEvent::MouseRightRelease]);
+    }
+
+    /// Scrolls the mouse wheel at the current mouse position, in pixels
+    pub fn scroll(&mut self, delta_x: f32, delta_y: f32) {
+        self.process_events(vec![InputEvent::Scroll { delta_x, delta_y }]);
+    }
+
+    /// Clicks the center of the given widget, where it's drawn once transformed
+    ///
+    /// Panics if the widget has no layout (i.e. it doesn't render a node).
+    pub fn click_widget(&mut self, id: Index) {
+        let layout = self
+            .get_layout(id)
+            .expect("Can't click a widget that has no layout!");
+        let transform = self.kayak_context.widget_manager.get_transform(&id);
+        self.click(transform.transform_point((
+            layout.posx + layout.width / 2.0,
+            layout.posy + layout.height / 2.0,
+        )));
+    }
+
+    /// Sends each character of `text` to the focused widget
+    pub fn type_text(&mut self, text: &str) {
+        self.process_events(text.chars().map(|c| InputEvent::CharEvent { c }).collect());
+    }
+
+    /// Sends a key press and release to the focused widget
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.process_events(vec![
+            InputEvent::Keyboard {
+                key,
+                is_pressed: true,
+            },
+            InputEvent::Keyboard {
+                key,
+                is_pressed: false,
+            },
+        ]);
+    }
+
+    /// Sends a key press without releasing it, e.g. to hold down a modifier key
+    pub fn key_down(&mut self, key: KeyCode) {
+        self.process_events(vec![InputEvent::Keyboard {
+            key,
+            is_pressed: true,
+        }]);
+    }
+
+    pub fn key_up(&mut self, key: KeyCode) {
+        self.process_events(vec![InputEvent::Keyboard {
+            key,
+            is_pressed: false,
+        }]);
+    }
+
+    /// Returns the ids of all mounted widgets, ordered depth-first from the root
+    pub fn widgets(&self) -> Vec<Index> {
+        self.kayak_context.widget_manager.tree.flatten()
+    }
+
+    pub fn find_widget_by_name(&self, name: &str) -> Option<Index> {
+        self.find_widgets_by_name(name).into_iter().next()
+    }
+
+    /// Finds all widgets whose [name](Widget::get_name) matches `name`
+    pub fn find_widgets_by_name(&self, name: &str) -> Vec<Index> {
+        let widget_manager = &self.kayak_context.widget_manager;
+        self.widgets()
+            .into_iter()
+            .filter(|id| {
+                matches!(
+                    widget_manager.current_widgets.get(*id),
+                    Some(Some(widget)) if widget.get_name() == name
+                )
+            })
+            .collect()
+    }
+
+    pub fn find_widget<T: Widget>(&self) -> Option<Index> {
+        self.find_widgets::<T>().into_iter().next()
+    }
+
+    /// Finds all widgets of type `T`
+    pub fn find_widgets<T: Widget>(&self) -> Vec<Index> {
+        self.widgets()
+            .into_iter()
+            .filter(|id| self.get_widget::<T>(*id).is_some())
+            .collect()
+    }
+
+    /// Returns the widget with the given id if it is of type `T`
+    pub fn get_widget<T: Widget>(&self, id: Index) -> Option<&T> {
+        match self.kayak_context.widget_manager.current_widgets.get(id) {
+            Some(Some(widget)) => (**widget).downcast_ref::<T>(),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the context still holds the state, effects, providers, bindings or unmount callbacks of the
+    /// given widget, e.g. to check that they were released when it was unmounted
+    pub fn has_widget_data(&self, id: Index) -> bool {
+        self.kayak_context.has_widget_data(id)
+    }
+
+    /// Returns the computed layout of the given widget
+    pub fn get_layout(&self, id: Index) -> Option<Rect> {
+        self.kayak_context.widget_manager.get_layout(&id).copied()
+    }
+
+    pub fn build_render_primitives(&self) -> Vec<RenderPrimitive> {
+        self.kayak_context.widget_manager.build_render_primitives()
+    }
+
+    /// Serializes the current render primitives, see [snapshot](crate::snapshot)
+    pub fn snapshot(&self) -> String {
+        snapshot::snapshot(&self.build_render_primitives())
+    }
+}
@@ -0,0 +1,212 @@
+use crate::layout_cache::Rect;
+
+/// Moves, scales and rotates a widget and its children after layout, like the CSS `transform` property
+///
+/// Transforms don't affect the layout of the widget or its siblings, only where it's drawn and where it receives
+/// mouse events. The widget is scaled, then rotated around its origin and finally translated:
+///
+/// ```ignore
+/// let pressed = Transform {
+///     scale: (0.95, 0.95),
+///     ..Transform::default()
+/// };
+/// let tilted = Transform {
+///     rotation: -5.0,
+///     origin: (0.0, 1.0),
+///     ..Transform::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// How far the widget is moved to the right and to the bottom, in pixels
+    pub translation: (f32, f32),
+    pub scale: (f32, f32),
+    /// The clockwise rotation, in degrees
+    pub rotation: f32,
+    /// The point the widget is scaled and rotated around, relative to its size (the center by default)
+    pub origin: (f32, f32),
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: (0.0, 0.0),
+            scale: (1.0, 1.0),
+            rotation: 0.0,
+            origin: (0.5, 0.5),
+        }
+    }
+}
+
+impl Transform {
+    /// Returns the matrix mapping the points of a widget with the given layout to where they are drawn
+    pub fn to_affine(&self, layout: &Rect) -> Affine {
+        let origin = (
+            layout.posx + layout.width * self.origin.0,
+            layout.posy + layout.height * self.origin.1,
+        );
+        Affine::translation(-origin.0, -origin.1)
+            .then(&Affine::scale(self.scale.0, self.scale.1))
+            .then(&Affine::rotation(self.rotation.to_radians()))
+            .then(&Affine::translation(
+                origin.0 + self.translation.0,
+                origin.1 + self.translation.1,
+            ))
+    }
+}
+
+/// A 2D affine transformation, mapping `(x, y)` to `(a * x + c * y + e, b * x + d * y + f)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    /// The coefficients `[a, b, c, d, e, f]`
+    pub matrix: [f32; 6],
+}
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Affine {
+    pub const IDENTITY: Affine = Affine {
+        matrix: [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+    };
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Self {
+            matrix: [1.0, 0.0, 0.0, 1.0, x, y],
+        }
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self {
+            matrix: [x, 0.0, 0.0, y, 0.0, 0.0],
+        }
+    }
+
+    /// A clockwise rotation (the y axis points down) around the top left corner of the screen
+    pub fn rotation(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            matrix: [cos, sin, -sin, cos, 0.0, 0.0],
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Returns the transformation applying this one, then `other`
+    pub fn then(&self, other: &Affine) -> Affine {
+        let [a, b, c, d, e, f] = self.matrix;
+        let [oa, ob, oc, od, oe, of] = other.matrix;
+        Affine {
+            matrix: [
+                oa * a + oc * b,
+                ob * a + od * b,
+                oa * c + oc * d,
+                ob * c + od * d,
+                oa * e + oc * f + oe,
+                ob * e + od * f + of,
+            ],
+        }
+    }
+
+    /// Returns the transformation undoing this one, or `None` if it flattens points on a line (e.g., a scale of 0)
+    pub fn inverse(&self) -> Option<Affine> {
+        let [a, b, c, d, e, f] = self.matrix;
+        let determinant = a * d - b * c;
+        if determinant.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let (a, b, c, d) = (
+            d / determinant,
+            -b / determinant,
+            -c / determinant,
+            a / determinant,
+        );
+        Some(Affine {
+            matrix: [a, b, c, d, -(a * e + c * f), -(b * e + d * f)],
+        })
+    }
+
+    pub fn transform_point(&self, point: (f32, f32)) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.matrix;
+        (a * point.0 + c * point.1 + e, b * point.0 + d * point.1 + f)
+    }
+
+    /// Returns the smallest axis-aligned rect containing the transformed rect, keeping its z index
+    pub fn transform_rect(&self, rect: &Rect) -> Rect {
+        let corners = [
+            (rect.posx, rect.posy),
+            (rect.posx + rect.width, rect.posy),
+            (rect.posx, rect.posy + rect.height),
+            (rect.posx + rect.width, rect.posy + rect.height),
+        ]
+        .map(|corner| self.transform_point(corner));
+        let (mut min_x, mut min_y) = corners[0];
+        let (mut max_x, mut max_y) = corners[0];
+        for (x, y) in corners {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+
+        Rect {
+            posx: min_x,
+            posy: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+            z_index: rect.z_index,
+        }
+    }
+}
+
+#[test]
+fn test_transform_points() {
+    let layout = Rect {
+        posx: 10.0,
+        posy: 20.0,
+        width: 100.0,
+        height: 50.0,
+        z_index: 0.0,
+    };
+    let close =
+        |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 0.001 && (a.1 - b.1).abs() < 0.001;
+
+    // Scaled around the center
+    let scaled = Transform {
+        scale: (2.0, 2.0),
+        ..Transform::default()
+    }
+    .to_affine(&layout);
+    assert!(close(scaled.transform_point((60.0, 45.0)), (60.0, 45.0)));
+    assert!(close(scaled.transform_point((10.0, 20.0)), (-40.0, -5.0)));
+
+    // Rotated clockwise around the top left corner, then moved
+    let rotated = Transform {
+        translation: (5.0, 0.0),
+        rotation: 90.0,
+        origin: (0.0, 0.0),
+        ..Transform::default()
+    }
+    .to_affine(&layout);
+    assert!(close(rotated.transform_point((110.0, 20.0)), (15.0, 120.0)));
+
+    let inverse = rotated.inverse().unwrap();
+    assert!(close(inverse.transform_point((15.0, 120.0)), (110.0, 20.0)));
+    assert!(close(
+        rotated.then(&inverse).transform_point((3.0, 4.0)),
+        (3.0, 4.0)
+    ));
+
+    let bounds = rotated.transform_rect(&layout);
+    assert!(close((bounds.posx, bounds.posy), (-35.0, 20.0)));
+    assert!(close((bounds.width, bounds.height), (50.0, 100.0)));
+
+    assert!(Transform::default().to_affine(&layout).is_identity());
+    assert!(Affine::scale(0.0, 1.0).inverse().is_none());
+}
@@ -62,6 +62,33 @@ impl Tree {
         }
     }
 
+    /// Removes the given node and all of its descendants from the tree
+    ///
+    /// Returns the removed nodes in depth-first order, starting with the given node.
+    pub fn remove(&mut self, index: Index) -> Vec<Index> {
+        if let Some(parent) = self.get_parent(index) {
+            if let Some(siblings) = self.children.get_mut(&parent) {
+                siblings.retain(|sibling| *sibling != index);
+            }
+        }
+
+        let mut removed = Vec::new();
+        let mut stack = vec![index];
+        while let Some(node) = stack.pop() {
+            removed.push(node);
+            if let Some(children) = self.children.remove(&node) {
+                stack.extend(children.into_iter().rev());
+            }
+            self.parents.remove(&node);
+        }
+
+        if self.root_node == Some(index) {
+            self.root_node = None;
+        }
+
+        removed
+    }
+
     pub fn flatten(&self) -> Vec<Index> {
         if self.root_node.is_none() {
             return Vec::new();
@@ -166,99 +193,34 @@ impl Tree {
 
         let mut child_changes = ChildChanges::default();
 
-        let children_a = children_a
-            .unwrap()
-            .into_iter()
-            .map(|i| *i)
-            .enumerate()
-            .collect::<Vec<(usize, Index)>>();
-        let children_b = children_b
-            .unwrap()
-            .into_iter()
-            .map(|i| *i)
-            .enumerate()
-            .collect::<Vec<(usize, Index)>>();
+        let children_a = children_a.unwrap();
+        let children_b = children_b.unwrap();
 
         let deleted_nodes = children_a
             .iter()
             // Find matching child
-            .filter(|(_, node)| !children_b.iter().any(|(_, node_b)| node == node_b))
-            .map(|(id, node)| (*id, *node, root_node, vec![Change::Deleted]))
+            .filter(|node| !children_b.contains(node))
+            .map(|node| (0, *node, root_node, vec![Change::Deleted]))
             .collect::<Vec<_>>();
         child_changes.changes.extend(deleted_nodes);
 
+        // Children are matched by identity so that a child that changed position is reported
+        // as moved rather than as an update of whichever node used to be at that position.
         let inserted_and_changed = children_b
             .iter()
+            .enumerate()
             .map(|(id, node)| {
-                let old_node = children_a.get(*id);
-                let inserted =
-                    old_node.is_some() && !children_a.iter().any(|(_, old_node)| node == old_node);
-
-                let value_changed = if let Some((_, old_node)) = old_node {
-                    node != old_node
-                } else {
-                    false
-                };
-                let changed = match (inserted, value_changed) {
-                    (true, false) => Change::Inserted,
-                    (true, true) => Change::Inserted,
-                    (false, true) => Change::Updated,
-                    (false, false) => Change::Unchanged,
+                let changed = match children_a.iter().position(|old_node| old_node == node) {
+                    None => Change::Inserted,
+                    Some(old_id) if old_id == id => Change::Unchanged,
+                    Some(_) => Change::Moved,
                 };
 
-                (*id, *node, root_node, vec![changed])
+                (id, *node, root_node, vec![changed])
             })
             .collect::<Vec<_>>();
         child_changes.changes.extend(inserted_and_changed);
 
-        let flat_tree_diff_nodes = child_changes
-            .changes
-            .iter()
-            .map(|(id, node, parent_node, change)| {
-                if change[0] == Change::Deleted {
-                    return (0, *node, *parent_node, change.clone());
-                } else if change[0] == Change::Inserted {
-                    let child_id = other_tree
-                        .children
-                        .get(parent_node)
-                        .unwrap()
-                        .iter()
-                        .position(|child| child == node)
-                        .unwrap();
-                    return (child_id, *node, *parent_node, change.clone());
-                }
-
-                let parent_a = self.parent(children_a.get(*id).unwrap().1);
-                let parent_b = self.parent(*node);
-                let definitely_moved = if parent_a.is_some() && parent_b.is_some() {
-                    let parent_a = parent_a.unwrap();
-                    let parent_b = parent_b.unwrap();
-                    parent_a != parent_b
-                        || (parent_a == parent_b
-                            && *node != children_a.get(*id).unwrap().1
-                            && children_a.iter().any(|(_, node_b)| node == node_b))
-                } else {
-                    false
-                };
-
-                if definitely_moved {
-                    let change = if change[0] == Change::Unchanged {
-                        vec![Change::Moved]
-                    } else {
-                        if change[0] == Change::Updated {
-                            vec![Change::Moved, Change::Updated]
-                        } else {
-                            vec![Change::Moved]
-                        }
-                    };
-                    return (*id, *node, *parent_node, change);
-                }
-
-                (*id, *node, *parent_node, change.clone())
-            })
-            .collect::<Vec<_>>();
-        child_changes.changes = flat_tree_diff_nodes;
-
         // for (child_id, child_node) in children_a.iter() {
         //     // Add children of child changes.
         //     let children_of_child_changes = self.diff_children(other_tree, *child_node);
@@ -383,38 +345,44 @@ impl Tree {
         flat_tree_diff_nodes
     }
 
-    pub fn merge(&mut self, other: &Tree, root_node: Index, changes: ChildChanges) {
+    /// Merges the children of `root_node` from `other` into this tree
+    ///
+    /// Returns every node that was removed from the tree as a result of the merge (including the
+    /// descendants of deleted children), so that they can be unmounted.
+    pub fn merge(&mut self, other: &Tree, root_node: Index, changes: ChildChanges) -> Vec<Index> {
         let has_changes = changes.has_changes();
+        let mut removed = Vec::new();
         let children_a = self.children.get_mut(&root_node);
         let children_b = other.children.get(&root_node);
         if children_a.is_none() && children_b.is_none() {
             // Nothing to do.
-            return;
+            return removed;
         } else if children_a.is_none() && children_b.is_some() {
             // Simple case of moving all children over to A.
             self.children.insert(root_node, children_b.unwrap().clone());
-            return;
+            return removed;
         } else if children_a.is_some() && children_b.is_none() {
             // Case for erasing all
             if has_changes {
-                let children_a = children_a.unwrap();
-                for child in children_a.iter() {
-                    self.parents.remove(&*child);
+                let children_a = children_a.unwrap().clone();
+                for child in children_a {
+                    removed.extend(self.remove(child));
                 }
                 self.children.remove(&root_node);
             }
-            return;
+            return removed;
         }
         let children_a = children_a.unwrap();
         let children_b = children_b.unwrap();
         children_a.resize(children_b.len(), Index::default());
+        let mut deleted_nodes = Vec::new();
         for (id, node, parent_node, change) in changes.changes.iter() {
             match change.as_slice() {
                 [Change::Inserted] => {
                     children_a[*id] = *node;
                     self.parents.insert(*node, *parent_node);
                 }
-                [Change::Moved, Change::Updated] => {
+                [Change::Moved] | [Change::Moved, Change::Updated] => {
                     children_a[*id] = *node;
                     self.parents.insert(*node, *parent_node);
                 }
@@ -422,12 +390,16 @@ impl Tree {
                     children_a[*id] = *node;
                 }
                 [Change::Deleted] => {
-                    self.parents.remove(node);
+                    deleted_nodes.push(*node);
                 }
                 _ => {}
             }
         }
 
+        for node in deleted_nodes {
+            removed.extend(self.remove(node));
+        }
+
         // for (child_id, children_of_child_changes) in changes.child_changes {
         //     self.merge(
         //         other,
@@ -435,6 +407,8 @@ impl Tree {
         //         children_of_child_changes,
         //     );
         // }
+
+        removed
     }
 }
 
@@ -632,3 +606,77 @@ fn test_tree() {
     assert!(mapped[3] == 3);
     assert!(mapped[4] == 4);
 }
+
+#[test]
+fn test_tree_remove() {
+    use crate::node::NodeBuilder;
+    use crate::Arena;
+    let mut store = Arena::new();
+    let root = store.insert(NodeBuilder::empty().build());
+    let index1 = store.insert(NodeBuilder::empty().build());
+    let index2 = store.insert(NodeBuilder::empty().build());
+    let index3 = store.insert(NodeBuilder::empty().build());
+
+    let mut tree = Tree::default();
+    tree.add(root, None);
+    tree.add(index1, Some(root));
+    tree.add(index2, Some(index1));
+    tree.add(index3, Some(root));
+
+    let removed = tree.remove(index1);
+
+    assert_eq!(removed, vec![index1, index2]);
+    assert_eq!(tree.children.get(&root), Some(&vec![index3]));
+    assert!(tree.get_parent(index1).is_none());
+    assert!(tree.get_parent(index2).is_none());
+    assert!(!tree.children.contains_key(&index1));
+}
+
+#[test]
+fn test_diff_children_moved() {
+    use crate::node::NodeBuilder;
+    use crate::Arena;
+    let mut store = Arena::new();
+    let root = store.insert(NodeBuilder::empty().build());
+    let index1 = store.insert(NodeBuilder::empty().build());
+    let index2 = store.insert(NodeBuilder::empty().build());
+    let index3 = store.insert(NodeBuilder::empty().build());
+    let index4 = store.insert(NodeBuilder::empty().build());
+
+    let mut tree = Tree::default();
+    tree.add(root, None);
+    tree.add(index1, Some(root));
+    tree.add(index2, Some(root));
+    tree.add(index3, Some(root));
+
+    // Insert a node at the front and remove the last one
+    let mut other_tree = Tree::default();
+    other_tree.add(root, None);
+    other_tree.add(index4, Some(root));
+    other_tree.add(index1, Some(root));
+    other_tree.add(index2, Some(root));
+
+    let changes = tree.diff_children(&other_tree, root);
+    let change_of = |node: Index| {
+        changes
+            .changes
+            .iter()
+            .find(|(_, changed_node, ..)| *changed_node == node)
+            .map(|(.., change)| change.clone())
+            .unwrap()
+    };
+
+    assert_eq!(change_of(index4), vec![Change::Inserted]);
+    assert_eq!(change_of(index1), vec![Change::Moved]);
+    assert_eq!(change_of(index2), vec![Change::Moved]);
+    assert_eq!(change_of(index3), vec![Change::Deleted]);
+
+    let removed = tree.merge(&other_tree, root, changes);
+
+    assert_eq!(removed, vec![index3]);
+    assert_eq!(
+        tree.children.get(&root),
+        Some(&vec![index4, index1, index2])
+    );
+    assert_eq!(tree.get_parent(index4), Some(root));
+}
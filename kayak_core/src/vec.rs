@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use derivative::*;
 
 use crate::{context::KayakContext, styles::Style, Index, Widget};
@@ -12,6 +14,9 @@ pub struct VecTracker<T> {
     #[derivative(Debug = "ignore", PartialEq = "ignore")]
     pub on_event: Option<crate::OnEvent>,
     pub data: Vec<T>,
+    /// Computes the key of each item, used to preserve the state of items when the list changes
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub key: Option<Arc<dyn Fn(&T) -> String + Send + Sync>>,
 }
 
 impl<T> VecTracker<T> {
@@ -22,8 +27,26 @@ impl<T> VecTracker<T> {
             styles: None,
             children: None,
             on_event: None,
+            key: None,
         }
     }
+
+    /// Sets the function used to compute the key of each item
+    ///
+    /// Without a key, items are matched with the widgets of the previous render by their position. This means
+    /// inserting or removing an item will shift the state of every following item onto its neighbour. With a key,
+    /// items are matched by identity instead, so each one keeps its own state. Keys must be unique.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// VecTracker::from(cards.iter().map(|card| constructor! { <Card id={card.id} /> }))
+    ///     .with_key(|card: &Card| card.id.to_string())
+    /// ```
+    pub fn with_key<F: Fn(&T) -> String + Send + Sync + 'static>(mut self, key: F) -> Self {
+        self.key = Some(Arc::new(key));
+        self
+    }
 }
 
 impl<T, I> From<I> for VecTracker<T>
@@ -67,10 +90,11 @@ where
         let tree = crate::WidgetTree::new();
 
         for (index, item) in self.data.iter().enumerate() {
+            let key = self.key.as_ref().map(|key| key(item));
             let (should_rerender, child_id) =
                 context
                     .widget_manager
-                    .create_widget(index, item.clone(), Some(self.get_id()));
+                    .create_widget(index, key, item.clone(), Some(self.get_id()));
             tree.add(child_id, Some(self.get_id()));
             if should_rerender {
                 let mut child_widget = context.widget_manager.take(child_id);
@@ -87,9 +111,12 @@ where
             .widget_manager
             .tree
             .diff_children(&tree, self.get_id());
-        context
+        let removed = context
             .widget_manager
             .tree
             .merge(&tree, self.get_id(), changes);
+
+        // Clean up any widgets that are no longer part of the tree.
+        context.unmount(removed);
     }
 }
@@ -4,10 +4,39 @@ use crate::{context::KayakContext, styles::Style, Event, Index};
 
 pub trait Widget: std::fmt::Debug + AsAny + Send + Sync {
     fn focusable(&self) -> bool;
+    /// Returns the position of this widget when cycling through focusable widgets with Tab
+    ///
+    /// Like the HTML `tabindex` attribute: widgets with a positive index come first, in ascending order, followed
+    /// by the widgets without one (or with `0`) in tree order. Widgets with a negative index can only be focused by
+    /// clicking them or with [`KayakContext::set_focus`], and are skipped by directional navigation as well.
+    fn tab_index(&self) -> Option<i32> {
+        None
+    }
     fn get_id(&self) -> Index;
     fn set_id(&mut self, id: Index);
     fn get_styles(&self) -> Option<Style>;
+    /// Returns the space separated list of [classes](crate::StyleSheet) this widget is styled with
+    fn get_class(&self) -> Option<String> {
+        None
+    }
+    /// Returns whether this widget is disabled, in which case it can't be focused and is styled with the
+    /// `disabled` variant of its classes
+    fn disabled(&self) -> bool {
+        false
+    }
     fn get_name(&self) -> String;
+    /// Returns a copy of this widget's props if it can skip rendering when they're unchanged since its last render
+    ///
+    /// Props are compared with `PartialEq`, which ignores `children` and `on_event`, and callbacks such as
+    /// `Handler` always compare equal. Widgets holding any of those should return `None`, since a skipped render
+    /// would keep the closures of the previous one.
+    fn memoized_props(&self) -> Option<Box<dyn Widget>> {
+        None
+    }
+    /// Handles an event targeting this widget or one of its descendants
+    ///
+    /// This is called for every [phase](crate::EventPhase) of the event that reaches this widget, including the
+    /// capture phase of events targeting its descendants.
     fn on_event(&mut self, context: &mut KayakContext, event: &mut Event);
     fn render(&mut self, context: &mut KayakContext);
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
@@ -8,12 +8,30 @@ use crate::{
     node::{Node, NodeBuilder},
     render_command::RenderCommand,
     render_primitive::RenderPrimitive,
-    styles::Style,
+    styles::{Style, StyleProp},
+    stylesheet::{Interaction, StyleSheet, StyleState},
+    transform::Affine,
     tree::Tree,
     Arena, Index, Widget,
 };
 use crate::layout_cache::Rect;
-// use as_any::Downcast;
+use as_any::Downcast;
+
+/// The effects of a widget's styles that also apply to all of its children when building render primitives
+#[derive(Debug, Clone, Copy)]
+struct SubtreeEffects {
+    opacity: f32,
+    transform: Affine,
+}
+
+impl Default for SubtreeEffects {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            transform: Affine::IDENTITY,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct WidgetManager {
@@ -21,6 +39,21 @@ pub struct WidgetManager {
     pub(crate) dirty_render_nodes: HashSet<Index>,
     pub(crate) dirty_nodes: Arc<Mutex<HashSet<Index>>>,
     pub(crate) nodes: Arena<Option<Node>>,
+    /// The keys of widgets created with a key, used to match children by identity
+    pub(crate) widget_keys: HashMap<Index, String>,
+    /// The children matched or created since their parent last started rendering, used to detect duplicate keys
+    rendered_children: HashMap<Index, HashSet<Index>>,
+    /// The props memoized widgets were last rendered with (see [Widget::memoized_props])
+    pub(crate) widget_props: HashMap<Index, Box<dyn Widget>>,
+    /// The classes widgets are styled with (see [KayakContext::set_stylesheet](crate::KayakContext::set_stylesheet))
+    pub(crate) stylesheet: StyleSheet,
+    /// The classes the stylesheet is applied on top of
+    /// (see [KayakContext::set_default_stylesheet](crate::KayakContext::set_default_stylesheet))
+    pub(crate) default_stylesheet: StyleSheet,
+    /// The stylesheet applied on top of the default one, which classes are resolved from
+    class_styles: StyleSheet,
+    /// The widgets the user interacts with, used to pick the state variants of their classes
+    pub(crate) interaction: Interaction,
     pub tree: Tree,
     pub node_tree: Tree,
     pub layout_cache: LayoutCache,
@@ -34,6 +67,13 @@ impl WidgetManager {
             dirty_render_nodes: HashSet::new(),
             dirty_nodes: Arc::new(Mutex::new(HashSet::new())),
             nodes: Arena::new(),
+            widget_keys: HashMap::new(),
+            rendered_children: HashMap::new(),
+            widget_props: HashMap::new(),
+            stylesheet: StyleSheet::default(),
+            default_stylesheet: StyleSheet::default(),
+            class_styles: StyleSheet::default(),
+            interaction: Interaction::default(),
             tree: Tree::default(),
             node_tree: Tree::default(),
             layout_cache: LayoutCache::default(),
@@ -58,40 +98,80 @@ impl WidgetManager {
         }
     }
 
+    /// Creates a widget or updates an existing one
+    ///
+    /// Existing children of `parent` are matched by `key` when one is given, allowing a widget (and its state)
+    /// to follow its data when siblings are inserted, removed or reordered. Keys must be unique among siblings:
+    /// an error is logged for duplicate keys, whose widgets are then matched in order. Children without a key are
+    /// matched by their positional `index` instead.
+    ///
+    /// Returns whether the widget should be rendered along with its id. Widgets that are
+    /// [memoized](Widget::memoized_props) and received the same props as their last render don't need to be
+    /// rendered again.
     pub fn create_widget<T: Widget + PartialEq + 'static>(
         &mut self,
         index: usize,
+        key: Option<String>,
         mut widget: T,
         parent: Option<Index>,
     ) -> (bool, Index) {
         if let Some(parent) = parent.clone() {
-            if let Some(parent_children) = self.tree.children.get_mut(&parent) {
+            if let Some(parent_children) = self.tree.children.get(&parent) {
+                let rendered_children = self.rendered_children.entry(parent).or_default();
+                let existing_child = if let Some(key) = &key {
+                    let keyed_children: Vec<Index> = parent_children
+                        .iter()
+                        .copied()
+                        .filter(|child| self.widget_keys.get(child) == Some(key))
+                        .collect();
+                    if keyed_children
+                        .iter()
+                        .any(|child| rendered_children.contains(child))
+                    {
+                        log::error!(
+                            "Found several children of {:?} with the key `{}`, keys must be unique among siblings",
+                            parent,
+                            key
+                        );
+                    }
+                    keyed_children
+                        .into_iter()
+                        .find(|child| !rendered_children.contains(child))
+                } else {
+                    parent_children
+                        .get(index)
+                        .copied()
+                        .filter(|child| !self.widget_keys.contains_key(child))
+                };
+
                 // Pull child and update.
-                if let Some(widget_id) = parent_children.get(index) {
-                    widget.set_id(*widget_id);
-                    // Remove from the dirty nodes lists.
-                    // if let Some(index) = self.dirty_nodes.iter().position(|id| *widget_id == *id) {
-                    //     self.dirty_nodes.remove(index);
-                    // }
-
-                    // TODO: Figure a good way of diffing props passed to children of a widget
-                    // that wont naturally-rerender it's children because of a lack of changes
-                    // to it's own props.
-                    // if &widget
-                    //     != self.current_widgets[*widget_id]
-                    //         .as_ref()
-                    //         .unwrap()
-                    //         .downcast_ref::<T>()
-                    //         .unwrap()
-                    // {
+                if let Some(widget_id) = existing_child {
+                    rendered_children.insert(widget_id);
+                    widget.set_id(widget_id);
+
+                    // Compare against the props of the last render, since rendering may have changed the
+                    // stored widget (e.g. its styles).
+                    if let Some(props) = widget.memoized_props() {
+                        let previous_props = self
+                            .widget_props
+                            .get(&widget_id)
+                            .and_then(|props| (**props).downcast_ref::<T>());
+                        if previous_props == Some(&widget) {
+                            // Inherited styles might still have changed, so the nodes of the widget and of
+                            // everything it rendered are rebuilt.
+                            self.mark_styles_dirty([widget_id]);
+                            return (false, widget_id);
+                        }
+                        self.widget_props.insert(widget_id, props);
+                    } else {
+                        self.widget_props.remove(&widget_id);
+                    }
+
                     let boxed_widget: Box<dyn Widget> = Box::new(widget);
-                    *self.current_widgets[*widget_id].as_mut().unwrap() = boxed_widget;
+                    *self.current_widgets[widget_id].as_mut().unwrap() = boxed_widget;
                     // Tell renderer that the nodes changed.
-                    self.dirty_render_nodes.insert(*widget_id);
-                    return (true, *widget_id);
-                    // } else {
-                    //     return (false, *widget_id);
-                    // }
+                    self.dirty_render_nodes.insert(widget_id);
+                    return (true, widget_id);
                 }
             }
         }
@@ -101,10 +181,21 @@ impl WidgetManager {
         // The root widget.
         let widget_id = self.current_widgets.insert(Some(Box::new(widget)));
         self.nodes.insert(None);
-        self.current_widgets[widget_id]
-            .as_mut()
-            .unwrap()
-            .set_id(widget_id);
+        let widget = self.current_widgets[widget_id].as_mut().unwrap();
+        widget.set_id(widget_id);
+        if let Some(props) = widget.memoized_props() {
+            self.widget_props.insert(widget_id, props);
+        }
+
+        if let Some(key) = key {
+            self.widget_keys.insert(widget_id, key);
+        }
+        if let Some(parent) = parent {
+            self.rendered_children
+                .entry(parent)
+                .or_default()
+                .insert(widget_id);
+        }
 
         // Tell renderer that the nodes changed.
         self.dirty_render_nodes.insert(widget_id);
@@ -120,7 +211,27 @@ impl WidgetManager {
         (true, widget_id)
     }
 
+    /// Removes a widget and all of its associated data from the manager
+    ///
+    /// This only removes the given widget. Its descendants should be removed from the tree first
+    /// (see [Tree::remove]) and passed to this method one by one.
+    pub fn remove(&mut self, id: Index) -> Option<Box<dyn Widget>> {
+        if let Ok(mut dirty_nodes) = self.dirty_nodes.lock() {
+            dirty_nodes.remove(&id);
+        }
+        self.dirty_render_nodes.remove(&id);
+        self.widget_keys.remove(&id);
+        self.rendered_children.remove(&id);
+        self.widget_props.remove(&id);
+        self.layout_cache.remove(id);
+        // Both arenas are kept in lockstep so that widget and node indices always match.
+        self.nodes.remove(id);
+        self.current_widgets.remove(id).flatten()
+    }
+
     pub fn take(&mut self, id: Index) -> Box<dyn Widget> {
+        // The widget is about to render its children again
+        self.rendered_children.remove(&id);
         self.current_widgets[id].take().unwrap()
     }
 
@@ -133,31 +244,191 @@ impl WidgetManager {
         self.layout_cache.rect.get(id)
     }
 
-    pub fn render(&mut self) {
-        let default_styles = Style {
-            background_color: crate::styles::StyleProp::Default,
-            border_radius: crate::styles::StyleProp::Default,
-            bottom: crate::styles::StyleProp::Default,
-            color: crate::styles::StyleProp::Default,
-            height: crate::styles::StyleProp::Default,
-            layout_type: crate::styles::StyleProp::Default,
-            left: crate::styles::StyleProp::Default,
-            padding_bottom: crate::styles::StyleProp::Default,
-            padding_left: crate::styles::StyleProp::Default,
-            padding_right: crate::styles::StyleProp::Default,
-            padding_top: crate::styles::StyleProp::Default,
-            position_type: crate::styles::StyleProp::Default,
-            render_command: crate::styles::StyleProp::Default,
-            right: crate::styles::StyleProp::Default,
-            top: crate::styles::StyleProp::Default,
-            width: crate::styles::StyleProp::Default,
-            ..Style::default()
+    /// Returns the transform mapping the layout of the given widget to where it's drawn, combining its `transform`
+    /// style with the ones of its parents
+    pub fn get_transform(&self, id: &Index) -> Affine {
+        let mut transform = Affine::IDENTITY;
+        let mut current = Some(*id);
+        while let Some(index) = current {
+            if let (Some(node), Some(layout)) = (self.get_node(&index), self.get_layout(&index)) {
+                if let StyleProp::Value(node_transform) = &node.styles.transform {
+                    transform = transform.then(&node_transform.to_affine(layout));
+                }
+            }
+            current = self.node_tree.get_parent(index);
+        }
+        transform
+    }
+
+    /// Returns whether a point (e.g., the mouse position) is within the layout of the given widget once transformed
+    pub fn contains_point(&self, id: &Index, point: &(f32, f32)) -> bool {
+        let layout = match self.get_layout(id) {
+            Some(layout) => layout,
+            None => return false,
+        };
+        let transform = self.get_transform(id);
+        if transform.is_identity() {
+            return layout.contains(point);
+        }
+        // A widget scaled down to nothing can't be hovered
+        transform.inverse().map_or(false, |inverse| {
+            layout.contains(&inverse.transform_point(*point))
+        })
+    }
+
+    /// Returns the node built for the given widget, whose styles are merged with its parent's
+    pub fn get_node(&self, id: &Index) -> Option<&Node> {
+        self.nodes.get(*id).and_then(|node| node.as_ref())
+    }
+
+    /// Replaces the stylesheet, rebuilding the styles of the widgets using a class that changed (and their
+    /// descendants) on the next render
+    pub(crate) fn set_stylesheet(&mut self, stylesheet: StyleSheet) {
+        self.stylesheet = stylesheet;
+        self.update_class_styles();
+    }
+
+    /// Replaces the default stylesheet, rebuilding the styles of the widgets using a class that changed (and
+    /// their descendants) on the next render
+    pub(crate) fn set_default_stylesheet(&mut self, stylesheet: StyleSheet) {
+        self.default_stylesheet = stylesheet;
+        self.update_class_styles();
+    }
+
+    fn update_class_styles(&mut self) {
+        let mut class_styles = self.default_stylesheet.clone();
+        class_styles.apply(&self.stylesheet);
+        let changed_classes = self.class_styles.changed_classes(&class_styles);
+        self.class_styles = class_styles;
+
+        let affected: Vec<Index> = self
+            .current_widgets
+            .iter()
+            .filter_map(|(index, widget)| {
+                let class = widget.as_ref()?.get_class()?;
+                class
+                    .split_whitespace()
+                    .any(|name| changed_classes.contains(name))
+                    .then(|| index)
+            })
+            .collect();
+        self.mark_styles_dirty(affected);
+    }
+
+    /// Updates the widgets the user interacts with, rebuilding the styles of the widgets whose state changed (and
+    /// their descendants, which may inherit from them) on the next render
+    pub(crate) fn set_interaction(&mut self, interaction: Interaction) {
+        if self.interaction == interaction {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.interaction, interaction.clone());
+        if !self.class_styles.has_variants() {
+            return;
+        }
+
+        // Hover and active apply to ancestors as well, which only change state below their common ancestor
+        let mut changed = HashSet::new();
+        for (before, after) in [
+            (previous.hovered, interaction.hovered),
+            (previous.active, interaction.active),
+        ] {
+            let before = self.ancestors_of(before);
+            let after = self.ancestors_of(after);
+            changed.extend(before.symmetric_difference(&after).copied());
+        }
+        let touched_ancestors = |touched: &[Index]| -> HashSet<Index> {
+            touched
+                .iter()
+                .flat_map(|index| self.ancestors_of(Some(*index)))
+                .collect()
         };
-        for dirty_node_index in self.dirty_render_nodes.drain() {
+        let before = touched_ancestors(&previous.touched);
+        let after = touched_ancestors(&interaction.touched);
+        changed.extend(before.symmetric_difference(&after).copied());
+        if previous.focused != interaction.focused {
+            changed.extend(previous.focused);
+            changed.extend(interaction.focused);
+        }
+
+        self.mark_styles_dirty(changed);
+    }
+
+    /// Rebuilds the nodes of the given widgets and their descendants on the next render, without rendering the
+    /// widgets again
+    fn mark_styles_dirty<I: IntoIterator<Item = Index>>(&mut self, indices: I) {
+        let mut stack: Vec<Index> = indices.into_iter().collect();
+        // Some of the widgets may already be dirty without their descendants, so visited widgets are tracked
+        // separately
+        let mut visited = HashSet::new();
+        while let Some(index) = stack.pop() {
+            if !self.current_widgets.contains(index) || !visited.insert(index) {
+                continue;
+            }
+            self.dirty_render_nodes.insert(index);
+            if let Some(children) = self.tree.children.get(&index) {
+                stack.extend(children.iter().copied());
+            }
+        }
+    }
+
+    /// Returns the given widget and all of its ancestors
+    fn ancestors_of(&self, index: Option<Index>) -> HashSet<Index> {
+        let mut ancestors = HashSet::new();
+        let mut current = index;
+        while let Some(index) = current {
+            ancestors.insert(index);
+            current = self.tree.get_parent(index);
+        }
+        ancestors
+    }
+
+    fn style_state(&self, index: Index, widget: &dyn Widget) -> StyleState {
+        let is_within = |target: Option<Index>| {
+            let mut current = target;
+            while let Some(current_index) = current {
+                if current_index == index {
+                    return true;
+                }
+                current = self.tree.get_parent(current_index);
+            }
+            false
+        };
+
+        StyleState {
+            hovered: is_within(self.interaction.hovered),
+            active: is_within(self.interaction.active)
+                || self
+                    .interaction
+                    .touched
+                    .iter()
+                    .any(|touched| is_within(Some(*touched))),
+            focused: self.interaction.focused == Some(index),
+            disabled: widget.disabled(),
+        }
+    }
+
+    pub fn render(&mut self) {
+        let default_styles = Style::initial();
+
+        // Parents are built before their children so that inherited styles are passed down the whole tree
+        let tree_order: HashMap<Index, usize> = self
+            .tree
+            .flatten()
+            .into_iter()
+            .enumerate()
+            .map(|(order, index)| (index, order))
+            .collect();
+        let mut dirty_nodes: Vec<Index> = self.dirty_render_nodes.drain().collect();
+        dirty_nodes.sort_by_key(|index| tree_order.get(index).copied().unwrap_or(usize::MAX));
+
+        for dirty_node_index in dirty_nodes {
             let dirty_widget = self.current_widgets[dirty_node_index].as_ref().unwrap();
             let parent_styles =
                 if let Some(parent_widget_id) = self.tree.parents.get(&dirty_node_index) {
-                    if let Some(parent) = self.current_widgets[*parent_widget_id].as_ref() {
+                    if let Some(Some(parent_node)) = self.nodes.get(*parent_widget_id) {
+                        // The parent's node holds its styles after they were merged with its own parent's
+                        parent_node.styles.clone()
+                    } else if let Some(parent) = self.current_widgets[*parent_widget_id].as_ref() {
                         if let Some(styles) = parent.get_styles() {
                             styles
                         } else {
@@ -192,17 +463,27 @@ impl WidgetManager {
                 }
             };
 
-            let mut styles = dirty_widget.get_styles();
-            if styles.is_some() {
-                styles.as_mut().unwrap().merge(&parent_styles);
-            }
+            // The widget's own styles are layered on top of its classes
+            let class_styles = dirty_widget.get_class().and_then(|class| {
+                let state = self.style_state(dirty_node_index, &**dirty_widget);
+                self.class_styles.resolve(&class, state)
+            });
+            let styles = match (class_styles, dirty_widget.get_styles()) {
+                (Some(mut class_styles), Some(widget_styles)) => {
+                    class_styles.apply(&widget_styles);
+                    Some(class_styles)
+                }
+                (class_styles, widget_styles) => class_styles.or(widget_styles),
+            };
+            // Widgets without styles still inherit from their parent (e.g., its cursor)
+            let mut styles = styles.unwrap_or_default();
+            styles.merge(&parent_styles);
             let children = self
                 .tree
                 .children
                 .get(&dirty_node_index)
                 .cloned()
                 .unwrap_or(vec![]);
-            let styles = styles.unwrap_or(default_styles.clone());
 
             let mut node = NodeBuilder::empty()
                 .with_id(dirty_node_index)
@@ -262,11 +543,24 @@ impl WidgetManager {
         current_node: Index,
         mut main_z_index: f32,
         mut prev_clip: RenderPrimitive,
+        parent_effects: SubtreeEffects,
     ) -> Vec<RenderPrimitive> {
         let mut render_primitives = Vec::new();
 
         if let Some(node) = nodes.get(current_node).unwrap() {
             if let Some(layout) = layout_cache.rect.get(&current_node) {
+                // Opacity multiplies down the tree, so that fading a widget fades its whole subtree
+                let opacity = match node.styles.opacity {
+                    StyleProp::Value(opacity) => parent_effects.opacity * opacity.clamp(0.0, 1.0),
+                    _ => parent_effects.opacity,
+                };
+                // Transforms are applied after layout, on top of the ones of the parent widgets
+                let transform = match &node.styles.transform {
+                    StyleProp::Value(node_transform) => node_transform
+                        .to_affine(layout)
+                        .then(&parent_effects.transform),
+                    _ => parent_effects.transform,
+                };
                 let mut render_primitive: RenderPrimitive = (&node.styles).into();
                 let mut layout = *layout;
                 let new_z_index = if matches!(render_primitive, RenderPrimitive::Clip { .. }) {
@@ -276,7 +570,45 @@ impl WidgetManager {
                 };
                 layout.z_index = new_z_index;
                 render_primitive.set_layout(layout);
+
+                // Shadows are drawn right behind the quad, or right above it when inset
+                let mut box_shadow = match &render_primitive {
+                    RenderPrimitive::Quad { border_radius, .. } => {
+                        node.styles.box_shadow.resolve().map(|box_shadow| {
+                            let z_offset = if box_shadow.inset { 0.01 } else { -0.01 };
+                            RenderPrimitive::BoxShadow {
+                                layout: Rect {
+                                    z_index: layout.z_index + z_offset,
+                                    ..layout
+                                },
+                                border_radius: *border_radius,
+                                box_shadow,
+                            }
+                        })
+                    }
+                    _ => None,
+                };
+                if opacity < 1.0 {
+                    render_primitive.apply_opacity(opacity);
+                    if let Some(box_shadow) = &mut box_shadow {
+                        box_shadow.apply_opacity(opacity);
+                    }
+                }
+                if !transform.is_identity() {
+                    let wrap = |primitive: RenderPrimitive| RenderPrimitive::Transformed {
+                        transform,
+                        primitive: Box::new(primitive),
+                    };
+                    if let RenderPrimitive::Clip { layout } = &mut render_primitive {
+                        // Clips stay axis-aligned, covering the area of the transformed widget
+                        *layout = transform.transform_rect(layout);
+                    } else if !matches!(render_primitive, RenderPrimitive::Empty) {
+                        render_primitive = wrap(render_primitive);
+                    }
+                    box_shadow = box_shadow.map(wrap);
+                }
                 render_primitives.push(render_primitive.clone());
+                render_primitives.extend(box_shadow);
 
                 let new_prev_clip = if matches!(render_primitive, RenderPrimitive::Clip { .. }) {
                     render_primitive.clone()
@@ -296,6 +628,7 @@ impl WidgetManager {
                             *child,
                             main_z_index,
                             new_prev_clip.clone(),
+                            SubtreeEffects { opacity, transform },
                         ));
 
                         main_z_index = layout.z_index;
@@ -326,6 +659,7 @@ impl WidgetManager {
             self.node_tree.root_node.unwrap(),
             0.0,
             RenderPrimitive::Empty,
+            SubtreeEffects::default(),
         )
     }
 
@@ -337,34 +671,37 @@ impl WidgetManager {
             tree.root_node.unwrap(),
             self.get_valid_node_children(tree.root_node.unwrap()),
         );
-        for (widget_id, widget) in self.current_widgets.iter().skip(1) {
-            let widget_styles = widget.as_ref().unwrap().get_styles();
-            if let Some(widget_styles) = widget_styles {
-                // Only add widgets who have renderable nodes.
-                if widget_styles.render_command.resolve() != RenderCommand::Empty {
-                    let valid_children = self.get_valid_node_children(widget_id);
-                    tree.children.insert(widget_id, valid_children);
-                    let valid_parent = self.get_valid_parent(widget_id);
-                    if let Some(valid_parent) = valid_parent {
-                        tree.parents.insert(widget_id, valid_parent);
-                    }
+        for (widget_id, _) in self.current_widgets.iter().skip(1) {
+            // Only add widgets who have renderable nodes.
+            if self.renders_node(widget_id) {
+                let valid_children = self.get_valid_node_children(widget_id);
+                tree.children.insert(widget_id, valid_children);
+                let valid_parent = self.get_valid_parent(widget_id);
+                if let Some(valid_parent) = valid_parent {
+                    tree.parents.insert(widget_id, valid_parent);
                 }
             }
         }
         tree
     }
 
+    /// Returns whether the widget has a render command, which can come from its classes as well as its styles
+    ///
+    /// The node of the widget holds its resolved styles, so it must have been built.
+    fn renders_node(&self, widget_id: Index) -> bool {
+        match self.nodes.get(widget_id) {
+            Some(Some(node)) => node.styles.render_command.resolve() != RenderCommand::Empty,
+            _ => false,
+        }
+    }
+
     fn get_valid_node_children(&self, node_id: Index) -> Vec<Index> {
         let mut children = Vec::new();
         if let Some(node_children) = self.tree.children.get(&node_id) {
             for child_id in node_children {
-                if let Some(child_widget) = &self.current_widgets[*child_id] {
-                    if let Some(child_styles) = child_widget.get_styles() {
-                        if child_styles.render_command.resolve() != RenderCommand::Empty {
-                            children.push(*child_id);
-                        } else {
-                            children.extend(self.get_valid_node_children(*child_id));
-                        }
+                if self.current_widgets[*child_id].is_some() {
+                    if self.renders_node(*child_id) {
+                        children.push(*child_id);
                     } else {
                         children.extend(self.get_valid_node_children(*child_id));
                     }
@@ -377,11 +714,9 @@ impl WidgetManager {
 
     fn get_valid_parent(&self, node_id: Index) -> Option<Index> {
         if let Some(parent_id) = self.tree.parents.get(&node_id) {
-            if let Some(parent_widget) = &self.current_widgets[*parent_id] {
-                if let Some(parent_styles) = parent_widget.get_styles() {
-                    if parent_styles.render_command.resolve() != RenderCommand::Empty {
-                        return Some(*parent_id);
-                    }
+            if self.current_widgets[*parent_id].is_some() {
+                if self.renders_node(*parent_id) {
+                    return Some(*parent_id);
                 }
 
                 return self.get_valid_parent(*parent_id);
@@ -51,6 +51,20 @@ impl KayakFont {
         }
     }
 
+    /// Creates a font from its SDF data alone, without an atlas texture asset
+    ///
+    /// This is useful when the atlas is sampled outside of bevy, such as by a software renderer.
+    pub fn from_sdf(sdf: Sdf) -> Self {
+        let mut font = Self {
+            sdf,
+            #[cfg(feature = "bevy_renderer")]
+            atlas_image: Handle::default(),
+            char_ids: HashMap::default(),
+        };
+        font.generate_char_ids();
+        font
+    }
+
     pub fn generate_char_ids(&mut self) {
         let mut count = 0;
         for glyph in self.sdf.glyphs.iter() {
@@ -6,6 +6,7 @@ pub fn build_arc_function(
     children_quotes: TokenStream,
     has_parent: bool,
     index: usize,
+    key: Option<TokenStream>,
     is_children: bool,
 ) -> TokenStream {
     let parent = if has_parent {
@@ -20,13 +21,21 @@ pub fn build_arc_function(
         quote! {}
     };
 
+    // The key is evaluated before the widget since building the widget may move the values it uses
+    let key = if let Some(key) = key {
+        quote! { Some((#key).to_string()) }
+    } else {
+        quote! { None }
+    };
+
     quote! {
         let children = children.clone();
+        let widget_key: Option<String> = #key;
         let #widget_name = #children_quotes;
         let (should_rerender, child_id) =
         context
             .widget_manager
-            .create_widget(#index, #widget_name, #parent);
+            .create_widget(#index, widget_key, #widget_name, #parent);
         #tree_add
         if should_rerender {
             let mut child_widget = context.widget_manager.take(child_id);
@@ -9,6 +9,16 @@ pub enum Child {
     RawBlock(syn::Block),
 }
 
+impl Child {
+    /// Returns the value of the `key` attribute of this child, if any
+    pub fn key(&self) -> Option<proc_macro2::TokenStream> {
+        match self {
+            Self::Widget(widget) => widget.attributes.key(),
+            Self::RawBlock(_) => None,
+        }
+    }
+}
+
 impl ToTokens for Child {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         match self {
@@ -100,6 +100,7 @@ impl Children {
                             quote! { #(#children_quotes),* },
                             true,
                             0,
+                            self.nodes[0].key(),
                             true,
                         );
 
@@ -165,6 +166,7 @@ impl Children {
                         children_quotes[i].clone(),
                         true,
                         i,
+                        self.nodes[i].key(),
                         true,
                     );
                     output.push(quote! { #child });
@@ -1,15 +1,53 @@
 use proc_macro::TokenStream;
 use proc_macro_error::emit_error;
 use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
+use syn::{Lit, Meta, Token};
 
 pub struct WidgetArguments {
     pub focusable: bool,
+    pub memo: bool,
+    /// The class every instance of the widget is styled with
+    pub class: Option<String>,
 }
 
 impl Default for WidgetArguments {
     fn default() -> Self {
-        Self { focusable: false }
+        Self {
+            focusable: false,
+            memo: true,
+            class: None,
+        }
+    }
+}
+
+impl Parse for WidgetArguments {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut widget_arguments = WidgetArguments::default();
+        for argument in Punctuated::<Meta, Token![,]>::parse_terminated(input)? {
+            match &argument {
+                Meta::Path(path) if path.is_ident("focusable") => widget_arguments.focusable = true,
+                Meta::Path(path) if path.is_ident("no_memo") => widget_arguments.memo = false,
+                Meta::NameValue(name_value) if name_value.path.is_ident("class") => {
+                    match &name_value.lit {
+                        Lit::Str(class) => widget_arguments.class = Some(class.value()),
+                        lit => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "expected a string, e.g. `class = \"button\"`",
+                            ))
+                        }
+                    }
+                }
+                _ => return Err(syn::Error::new(
+                    argument.span(),
+                    "unknown widget argument, expected `focusable`, `no_memo` or `class = \"...\"`",
+                )),
+            }
+        }
+        Ok(widget_arguments)
     }
 }
 
@@ -34,6 +72,7 @@ pub fn create_function_widget(f: syn::ItemFn, widget_arguments: WidgetArguments)
     };
 
     let focusable = widget_arguments.focusable;
+    let memo = widget_arguments.memo;
 
     let mut input_names: Vec<_> = inputs
         .iter()
@@ -88,7 +127,7 @@ pub fn create_function_widget(f: syn::ItemFn, widget_arguments: WidgetArguments)
         }
     });
 
-    let missing_struct_inputs = vec![
+    let mut missing_struct_inputs = vec![
         (
             vec![
                 "styles : Option < Style >",
@@ -99,6 +138,13 @@ pub fn create_function_widget(f: syn::ItemFn, widget_arguments: WidgetArguments)
                 pub styles: Option<#kayak_core::styles::Style>
             },
         ),
+        (
+            vec!["class : Option < String >"],
+            quote! {
+                #[derivative(Default(value="None"))]
+                pub class: Option<String>
+            },
+        ),
         (
             vec!["children : Children"],
             quote! {
@@ -119,6 +165,23 @@ pub fn create_function_widget(f: syn::ItemFn, widget_arguments: WidgetArguments)
         ),
     ];
 
+    // Focusable widgets can set their position in the tab order
+    if focusable {
+        missing_struct_inputs.push((
+            vec!["tab_index : Option < i32 >"],
+            quote! {
+                #[derivative(Default(value="None"))]
+                pub tab_index: Option<i32>
+            },
+        ));
+        missing_struct_inputs.push((
+            vec!["disabled : bool"],
+            quote! {
+                pub disabled: bool
+            },
+        ));
+    }
+
     for (names, token) in missing_struct_inputs {
         if !input_block_names.iter().any(|block_name| {
             names
@@ -154,6 +217,43 @@ pub fn create_function_widget(f: syn::ItemFn, widget_arguments: WidgetArguments)
         )
     };
 
+    // Disabled widgets can't be focused
+    let focusable = if focusable {
+        quote! {
+            !self.disabled
+        }
+    } else {
+        quote! {
+            false
+        }
+    };
+
+    let focus_methods = if widget_arguments.focusable {
+        quote! {
+            fn tab_index(&self) -> Option<i32> {
+                self.tab_index
+            }
+
+            fn disabled(&self) -> bool {
+                self.disabled
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let class = match widget_arguments.class {
+        Some(widget_class) => quote! {
+            match &self.class {
+                Some(class) => Some(format!("{} {}", #widget_class, class)),
+                None => Some(String::from(#widget_class)),
+            }
+        },
+        None => quote! {
+            self.class.clone()
+        },
+    };
+
     TokenStream::from(quote! {
         use #kayak_core::derivative::*;
 
@@ -173,23 +273,35 @@ pub fn create_function_widget(f: syn::ItemFn, widget_arguments: WidgetArguments)
                 #focusable
             }
 
+            #focus_methods
+
             fn set_id(&mut self, id: #kayak_core::Index) {
                 self.id = id;
             }
 
+            fn memoized_props(&self) -> Option<Box<dyn #kayak_core::Widget>> {
+                if #memo && self.children.is_none() && self.on_event.is_none() {
+                    Some(Box::new(self.clone()))
+                } else {
+                    None
+                }
+            }
+
             fn get_styles(&self) -> Option<#kayak_core::styles::Style> {
                 self.styles.clone()
             }
 
+            fn get_class(&self) -> Option<String> {
+                #class
+            }
+
             fn get_name(&self) -> String {
                 String::from(stringify!(#struct_name))
             }
 
             fn on_event(&mut self, context: &mut #kayak_core::context::KayakContext, event: &mut #kayak_core::Event) {
                 if let Some(on_event) = self.on_event.as_ref() {
-                    if let Ok(mut on_event) = on_event.0.write() {
-                        on_event(context, event);
-                    }
+                    on_event.call(context, event);
                 }
             }
 
@@ -212,10 +324,13 @@ pub fn create_function_widget(f: syn::ItemFn, widget_arguments: WidgetArguments)
                     .tree
                     .diff_children(&tree, self.get_id());
 
-                context
+                let removed = context
                     .widget_manager
                     .tree
                     .merge(&tree, self.get_id(), changes);
+
+                // Clean up any widgets that are no longer part of the tree.
+                context.unmount(removed);
             }
         }
     })
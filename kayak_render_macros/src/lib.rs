@@ -52,6 +52,10 @@ pub fn render(input: TokenStream) -> TokenStream {
 }
 
 /// Generate a renderable widget tree, before rendering it
+///
+/// Any widget can be given a `key` attribute (e.g. `<Card key={card.id} />`). Keyed widgets are matched
+/// with the widgets of the previous render by key instead of by position, preserving their state when
+/// their siblings change. Keys must be unique among siblings.
 #[proc_macro]
 #[proc_macro_error]
 pub fn rsx(input: TokenStream) -> TokenStream {
@@ -60,6 +64,9 @@ pub fn rsx(input: TokenStream) -> TokenStream {
     TokenStream::from(result)
 }
 
+/// Builds a widget without rendering it, e.g. to pass it to a `VecTracker`
+///
+/// Keys aren't supported here, use `VecTracker::with_key` to key the widgets of a list instead.
 #[proc_macro]
 #[proc_macro_error]
 pub fn constructor(input: TokenStream) -> TokenStream {
@@ -69,16 +76,22 @@ pub fn constructor(input: TokenStream) -> TokenStream {
     TokenStream::from(result)
 }
 
+/// Turns a function into a widget
+///
+/// Widgets without `children` or an `on_event` handler skip rendering when their parent renders them with the same
+/// props as last time. Props are compared with `PartialEq`, so widgets taking callbacks such as `Handler` need
+/// `#[widget(no_memo)]`: callbacks always compare equal, and the widget would keep calling the ones it was first
+/// rendered with.
+///
+/// Use `#[widget(focusable)]` to allow the widget to receive focus. Focusable widgets get a `tab_index: Option<i32>`
+/// prop which sets their position in the Tab order (see `Widget::tab_index`), and a `disabled: bool` prop.
+///
+/// Every widget gets a `class: Option<String>` prop listing the stylesheet classes it's styled with. Use
+/// `#[widget(class = "button")]` to give the widget a class of its own, which comes before the ones passed as a prop.
 #[proc_macro_attribute]
 #[proc_macro_error]
 pub fn widget(args: TokenStream, item: TokenStream) -> TokenStream {
-    let mut widget_args = WidgetArguments::default();
-    if !args.is_empty() {
-        // Parse stuff..
-        let parsed = args.to_string();
-        widget_args.focusable = parsed.contains("focusable");
-    }
-
+    let widget_args = parse_macro_input!(args as WidgetArguments);
     let f = parse_macro_input!(item as syn::ItemFn);
     function_component::create_function_widget(f, widget_args)
 }
@@ -192,6 +205,10 @@ pub fn use_state(initial_state: TokenStream) -> TokenStream {
 /// This can be seen in the example below where `count_state` is used within the callback and in
 /// the dependency array.
 ///
+/// The callback may also return a cleanup closure. This closure runs right before the callback is
+/// invoked again and when the widget is unmounted, which makes it the place to unsubscribe from
+/// anything the callback subscribed to.
+///
 /// For more details, check out [React's documentation](https://reactjs.org/docs/hooks-effect.html),
 /// upon which this macro is based.
 ///
@@ -214,6 +231,11 @@ pub fn use_state(initial_state: TokenStream) -> TokenStream {
 ///     println!("Count: {}", count_state.get());
 /// }, [count_state]);
 ///
+/// use_effect!(move || {
+///     println!("Mounted!");
+///     || println!("Unmounted!")
+/// }, []);
+///
 /// let on_event = OnEvent::new(move |_, event| match event.event_type {
 ///         EventType::Click => {
 ///             set_count(foo + 1);
@@ -1,4 +1,5 @@
 use proc_macro2::TokenStream;
+use proc_macro_error::emit_error;
 use quote::quote;
 use quote::ToTokens;
 use syn::parse::{Parse, ParseStream, Result};
@@ -64,12 +65,24 @@ impl Widget {
             let attrs = attrs.to_token_stream();
             if !as_prop {
                 let attrs = quote! { #name #attrs };
-                let widget_block =
-                    build_arc_function(quote! { built_widget }, attrs, has_parent, 0, true);
+                let widget_block = build_arc_function(
+                    quote! { built_widget },
+                    attrs,
+                    has_parent,
+                    0,
+                    open_tag.attributes.key(),
+                    true,
+                );
                 quote! {
                     #widget_block
                 }
             } else {
+                if let Some(key) = open_tag.attributes.key() {
+                    emit_error!(
+                        key,
+                        "`key` isn't supported by `constructor!`, use `VecTracker::with_key` to key a list"
+                    );
+                }
                 quote! {
                     #name #attrs
                 }
@@ -26,6 +26,16 @@ impl WidgetAttributes {
         }
     }
 
+    /// Returns the value of the `key` attribute, if any
+    ///
+    /// The key isn't a prop of the widget, it's passed to the widget manager to match children by identity.
+    pub fn key(&self) -> Option<proc_macro2::TokenStream> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.ident().to_token_stream().to_string() == "key")
+            .map(|attribute| attribute.value_tokens())
+    }
+
     pub fn custom_parse(input: ParseStream) -> Result<Self> {
         let mut parsed_self = input.parse::<Self>()?;
         let new_attributes: HashSet<Attribute> = parsed_self
@@ -73,6 +83,7 @@ impl<'a, 'c> ToTokens for CustomWidgetAttributes<'a, 'c> {
         let mut attrs: Vec<_> = self
             .attributes
             .iter()
+            .filter(|attribute| attribute.ident().to_token_stream().to_string() != "key")
             .map(|attribute| {
                 let ident = attribute.ident();
                 let value = attribute.value_tokens();
@@ -0,0 +1,473 @@
+use kayak_core::{color::Color, layout_cache::Rect, Affine, Background, BoxShadow};
+
+use crate::Image;
+
+/// An axis-aligned rectangle in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Bounds {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl Bounds {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            min_x: x,
+            min_y: y,
+            max_x: x + width,
+            max_y: y + height,
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max_x - self.min_x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max_y - self.min_y
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.max_x <= self.min_x || self.max_y <= self.min_y
+    }
+
+    pub fn intersect(&self, other: &Bounds) -> Bounds {
+        Bounds {
+            min_x: self.min_x.max(other.min_x),
+            min_y: self.min_y.max(other.min_y),
+            max_x: self.max_x.min(other.max_x),
+            max_y: self.max_y.min(other.max_y),
+        }
+    }
+
+    /// Returns how much of the pixel at the given coordinates is covered, from 0.0 to 1.0
+    pub fn pixel_coverage(&self, x: u32, y: u32) -> f32 {
+        let (x, y) = (x as f32, y as f32);
+        let covered_x = (self.max_x.min(x + 1.0) - self.min_x.max(x)).max(0.0);
+        let covered_y = (self.max_y.min(y + 1.0) - self.min_y.max(y)).max(0.0);
+        covered_x * covered_y
+    }
+}
+
+impl From<&Rect> for Bounds {
+    fn from(layout: &Rect) -> Self {
+        Self::new(layout.posx, layout.posy, layout.width, layout.height)
+    }
+}
+
+fn smooth_step(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge1 <= edge0 {
+        return if x < edge0 { 0.0 } else { 1.0 };
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Returns how much of the pixel at the given coordinates is covered by a rectangle with rounded corners
+fn rounded_rect_coverage(
+    rect: &Bounds,
+    border_radius: (f32, f32, f32, f32),
+    x: u32,
+    y: u32,
+) -> f32 {
+    if rect.is_empty() {
+        return 0.0;
+    }
+
+    let max_radius = rect.width().min(rect.height()) / 2.0;
+    let center = (
+        (rect.min_x + rect.max_x) / 2.0,
+        (rect.min_y + rect.max_y) / 2.0,
+    );
+    let point = (x as f32 + 0.5, y as f32 + 0.5);
+    let radius = match (point.0 < center.0, point.1 < center.1) {
+        (true, false) => border_radius.0,
+        (true, true) => border_radius.1,
+        (false, true) => border_radius.2,
+        (false, false) => border_radius.3,
+    }
+    .clamp(0.0, max_radius.max(0.0));
+
+    if radius > 0.0 {
+        // Distance from the rectangle shrunk by the radius, as computed by `sd_box_rounded` in the shader
+        let dx = (rect.min_x + radius - point.0)
+            .max(point.0 - (rect.max_x - radius))
+            .max(0.0);
+        let dy = (rect.min_y + radius - point.1)
+            .max(point.1 - (rect.max_y - radius))
+            .max(0.0);
+        let distance = (dx * dx + dy * dy).sqrt();
+        1.0 - smooth_step((radius - 0.5).max(0.0), radius + 0.5, distance)
+    } else {
+        rect.pixel_coverage(x, y)
+    }
+}
+
+/// Returns the signed distance from a point to the edge of a rectangle with rounded corners, negative inside of it
+fn rounded_rect_distance(
+    rect: &Bounds,
+    border_radius: (f32, f32, f32, f32),
+    point: (f32, f32),
+) -> f32 {
+    let half_size = (
+        (rect.width() / 2.0).max(0.0),
+        (rect.height() / 2.0).max(0.0),
+    );
+    let center = (rect.min_x + half_size.0, rect.min_y + half_size.1);
+    let radius = match (point.0 < center.0, point.1 < center.1) {
+        (true, false) => border_radius.0,
+        (true, true) => border_radius.1,
+        (false, true) => border_radius.2,
+        (false, false) => border_radius.3,
+    }
+    .clamp(0.0, half_size.0.min(half_size.1));
+
+    let qx = (point.0 - center.0).abs() - half_size.0 + radius;
+    let qy = (point.1 - center.1).abs() - half_size.1 + radius;
+    let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+    outside + qx.max(qy).min(0.0) - radius
+}
+
+/// The pixel buffer primitives are drawn onto
+pub(crate) struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, clear_color: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![
+                [clear_color.r, clear_color.g, clear_color.b, clear_color.a];
+                (width * height) as usize
+            ],
+        }
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(0.0, 0.0, self.width as f32, self.height as f32)
+    }
+
+    /// Returns the coordinates of the pixels touched by `bounds`
+    fn pixels_in(&self, bounds: &Bounds) -> impl Iterator<Item = (u32, u32)> {
+        let bounds = bounds.intersect(&self.bounds());
+        let (min_x, max_x) = (bounds.min_x.floor() as u32, bounds.max_x.ceil() as u32);
+        let (min_y, max_y) = (bounds.min_y.floor() as u32, bounds.max_y.ceil() as u32);
+        let is_empty = bounds.is_empty();
+        (min_y..max_y)
+            .flat_map(move |y| (min_x..max_x).map(move |x| (x, y)))
+            .filter(move |_| !is_empty)
+    }
+
+    /// Blends a color with straight alpha over the pixel at the given coordinates
+    fn blend(&mut self, x: u32, y: u32, color: [f32; 4]) {
+        let alpha = color[3].clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+
+        let pixel = &mut self.pixels[(y * self.width + x) as usize];
+        let kept_alpha = pixel[3] * (1.0 - alpha);
+        let out_alpha = alpha + kept_alpha;
+        let blend_channel =
+            |source: f32, destination: f32| (source * alpha + destination * kept_alpha) / out_alpha;
+        *pixel = [
+            blend_channel(color[0], pixel[0]),
+            blend_channel(color[1], pixel[1]),
+            blend_channel(color[2], pixel[2]),
+            out_alpha,
+        ];
+    }
+
+    /// Fills a rectangle with rounded corners with a color or a gradient and draws its border inside of it
+    ///
+    /// The corner radii use the same order as the GPU renderer: bottom left, top left, top right, bottom right. The
+    /// border widths are in the order top, right, bottom, left.
+    pub fn fill_rounded_rect(
+        &mut self,
+        rect: &Bounds,
+        border_radius: (f32, f32, f32, f32),
+        background: &Background,
+        border: (f32, f32, f32, f32),
+        border_color: &Color,
+        clip: &Bounds,
+    ) {
+        let (top, right, bottom, left) = border;
+        let inner_rect = Bounds {
+            min_x: rect.min_x + left,
+            min_y: rect.min_y + top,
+            max_x: rect.max_x - right,
+            max_y: rect.max_y - bottom,
+        };
+        // Like in the shader, the inner corners are rounded by what's left of the radius once the border is removed
+        let widest_side = top.max(right).max(bottom).max(left);
+        let inner_radius = |radius: f32| (radius - widest_side).max(0.0);
+        let inner_border_radius = (
+            inner_radius(border_radius.0),
+            inner_radius(border_radius.1),
+            inner_radius(border_radius.2),
+            inner_radius(border_radius.3),
+        );
+        let has_border = widest_side > 0.0;
+        let layout = Rect {
+            posx: rect.min_x,
+            posy: rect.min_y,
+            width: rect.width(),
+            height: rect.height(),
+            z_index: 0.0,
+        };
+
+        for (x, y) in self.pixels_in(&rect.intersect(clip)) {
+            let color = background.color_at(&layout, (x as f32 + 0.5, y as f32 + 0.5));
+            let shape_coverage = rounded_rect_coverage(rect, border_radius, x, y);
+            let fill_coverage = if has_border {
+                rounded_rect_coverage(&inner_rect, inner_border_radius, x, y)
+            } else {
+                1.0
+            };
+            let mix = |border: f32, fill: f32| border + (fill - border) * fill_coverage;
+            let pixel_color = [
+                mix(border_color.r, color.r),
+                mix(border_color.g, color.g),
+                mix(border_color.b, color.b),
+                mix(border_color.a, color.a),
+            ];
+
+            let coverage = shape_coverage * clip.pixel_coverage(x, y);
+            self.blend(
+                x,
+                y,
+                [
+                    pixel_color[0],
+                    pixel_color[1],
+                    pixel_color[2],
+                    pixel_color[3] * coverage,
+                ],
+            );
+        }
+    }
+
+    /// Draws the shadow of a rectangle with rounded corners, the same way the shader does
+    ///
+    /// Shadows are only drawn outside of the rectangle, or inside of it when inset.
+    pub fn draw_box_shadow(
+        &mut self,
+        rect: &Bounds,
+        border_radius: (f32, f32, f32, f32),
+        box_shadow: &BoxShadow,
+        clip: &Bounds,
+    ) {
+        // Inset shadows are cast by the area around a hole that shrinks as the spread grows
+        let spread = if box_shadow.inset {
+            -box_shadow.spread
+        } else {
+            box_shadow.spread
+        };
+        let (offset_x, offset_y) = box_shadow.offset;
+        let shadow_rect = Bounds {
+            min_x: rect.min_x + offset_x - spread,
+            min_y: rect.min_y + offset_y - spread,
+            max_x: rect.max_x + offset_x + spread,
+            max_y: rect.max_y + offset_y + spread,
+        };
+        let spread_radius = |radius: f32| (radius + spread).max(0.0);
+        let shadow_radius = (
+            spread_radius(border_radius.0),
+            spread_radius(border_radius.1),
+            spread_radius(border_radius.2),
+            spread_radius(border_radius.3),
+        );
+        // Keep half a pixel of antialiasing for shadows without blur
+        let blur = box_shadow.blur.max(0.5);
+
+        let area = if box_shadow.inset {
+            *rect
+        } else {
+            Bounds {
+                min_x: shadow_rect.min_x - blur,
+                min_y: shadow_rect.min_y - blur,
+                max_x: shadow_rect.max_x + blur,
+                max_y: shadow_rect.max_y + blur,
+            }
+        };
+        let color = box_shadow.color;
+        for (x, y) in self.pixels_in(&area.intersect(clip)) {
+            let point = (x as f32 + 0.5, y as f32 + 0.5);
+            let distance = rounded_rect_distance(&shadow_rect, shadow_radius, point);
+            let shadow_coverage = 1.0 - smooth_step(-blur, blur, distance);
+            let rect_coverage = rounded_rect_coverage(rect, border_radius, x, y);
+            let coverage = if box_shadow.inset {
+                rect_coverage * (1.0 - shadow_coverage)
+            } else {
+                shadow_coverage * (1.0 - rect_coverage)
+            };
+
+            self.blend(
+                x,
+                y,
+                [
+                    color.r,
+                    color.g,
+                    color.b,
+                    color.a * coverage * clip.pixel_coverage(x, y),
+                ],
+            );
+        }
+    }
+
+    /// Draws the `source` region of an image (in pixels) stretched over `destination`
+    pub fn draw_image(
+        &mut self,
+        image: &Image,
+        source: &Bounds,
+        destination: &Bounds,
+        opacity: f32,
+        clip: &Bounds,
+    ) {
+        if destination.is_empty() || source.is_empty() {
+            return;
+        }
+
+        for (x, y) in self.pixels_in(&destination.intersect(clip)) {
+            let coverage = destination.pixel_coverage(x, y) * clip.pixel_coverage(x, y);
+            if coverage <= 0.0 {
+                continue;
+            }
+
+            let u = (x as f32 + 0.5 - destination.min_x) / destination.width();
+            let v = (y as f32 + 0.5 - destination.min_y) / destination.height();
+            // Don't sample outside of the source region so that neighboring regions don't bleed in
+            let sample_x = (source.min_x + u * source.width()).clamp(
+                source.min_x + 0.5,
+                (source.max_x - 0.5).max(source.min_x + 0.5),
+            );
+            let sample_y = (source.min_y + v * source.height()).clamp(
+                source.min_y + 0.5,
+                (source.max_y - 0.5).max(source.min_y + 0.5),
+            );
+
+            let mut color = image.sample(sample_x, sample_y);
+            color[3] *= coverage * opacity;
+            self.blend(x, y, color);
+        }
+    }
+
+    /// Draws the `glyph` region of a multi-channel signed distance field atlas stretched over `destination`
+    ///
+    /// `screen_px_range` is the distance range of the atlas in screen pixels.
+    pub fn draw_msdf_glyph(
+        &mut self,
+        atlas: &Image,
+        glyph: &Bounds,
+        destination: &Bounds,
+        screen_px_range: f32,
+        color: &Color,
+        clip: &Bounds,
+    ) {
+        if destination.is_empty() || glyph.is_empty() {
+            return;
+        }
+
+        for (x, y) in self.pixels_in(&destination.intersect(clip)) {
+            let offset_x =
+                (x as f32 + 0.5 - destination.min_x) / destination.width() * glyph.width();
+            let offset_y =
+                (y as f32 + 0.5 - destination.min_y) / destination.height() * glyph.height();
+
+            let sample = atlas.sample(glyph.min_x + offset_x, glyph.min_y + offset_y);
+            let median = sample[0]
+                .min(sample[1])
+                .max(sample[0].max(sample[1]).min(sample[2]));
+            let signed_distance = (median - 0.5) * screen_px_range;
+            let alpha = (signed_distance + 0.5).clamp(0.0, 1.0);
+
+            let coverage = alpha * clip.pixel_coverage(x, y);
+            self.blend(x, y, [color.r, color.g, color.b, color.a * coverage]);
+        }
+    }
+
+    /// Draws another canvas, mapping its pixels to this one with `transform`
+    pub fn draw_layer(&mut self, layer: &Canvas, transform: &Affine, clip: &Bounds) {
+        let inverse = match transform.inverse() {
+            Some(inverse) => inverse,
+            None => return,
+        };
+
+        let destination = transform.transform_rect(&Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width: layer.width as f32,
+            height: layer.height as f32,
+            z_index: 0.0,
+        });
+        for (x, y) in self.pixels_in(&Bounds::from(&destination).intersect(clip)) {
+            let coverage = clip.pixel_coverage(x, y);
+            if coverage <= 0.0 {
+                continue;
+            }
+
+            let (layer_x, layer_y) = inverse.transform_point((x as f32 + 0.5, y as f32 + 0.5));
+            let mut color = layer.sample(layer_x, layer_y);
+            color[3] *= coverage;
+            self.blend(x, y, color);
+        }
+    }
+
+    /// Bilinearly samples the canvas at the given position in pixels, transparent outside of it
+    ///
+    /// Colors are weighted by their alpha so that transparent pixels don't darken the edges.
+    fn sample(&self, x: f32, y: f32) -> [f32; 4] {
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let texels = [
+            (x0, y0, (1.0 - tx) * (1.0 - ty)),
+            (x0 + 1.0, y0, tx * (1.0 - ty)),
+            (x0, y0 + 1.0, (1.0 - tx) * ty),
+            (x0 + 1.0, y0 + 1.0, tx * ty),
+        ];
+
+        let mut premultiplied = [0.0; 4];
+        for (texel_x, texel_y, weight) in texels {
+            if texel_x < 0.0
+                || texel_y < 0.0
+                || texel_x >= self.width as f32
+                || texel_y >= self.height as f32
+            {
+                continue;
+            }
+            let pixel = self.pixels[(texel_y as u32 * self.width + texel_x as u32) as usize];
+            let alpha = pixel[3] * weight;
+            premultiplied[0] += pixel[0] * alpha;
+            premultiplied[1] += pixel[1] * alpha;
+            premultiplied[2] += pixel[2] * alpha;
+            premultiplied[3] += alpha;
+        }
+
+        let alpha = premultiplied[3];
+        if alpha <= 0.0 {
+            return [0.0; 4];
+        }
+        [
+            premultiplied[0] / alpha,
+            premultiplied[1] / alpha,
+            premultiplied[2] / alpha,
+            alpha,
+        ]
+    }
+
+    pub fn into_image(self) -> Image {
+        let mut pixels = Vec::with_capacity(self.pixels.len() * 4);
+        for pixel in self.pixels {
+            for channel in pixel {
+                pixels.push((channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+        Image::from_rgba(self.width, self.height, pixels)
+    }
+}
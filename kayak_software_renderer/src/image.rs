@@ -0,0 +1,202 @@
+use std::{io::BufWriter, path::Path};
+
+/// An 8-bit RGBA image with straight (non-premultiplied) alpha
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// The pixels in rows from top to bottom, four bytes per pixel
+    pub pixels: Vec<u8>,
+}
+
+/// The result of comparing two images with [Image::diff]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageDiff {
+    /// The number of pixels with a channel differing by more than the tolerance
+    pub different_pixels: usize,
+    /// The largest difference found in any channel
+    pub max_difference: u8,
+    /// An image highlighting the differing pixels in red
+    pub image: Image,
+}
+
+impl ImageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.different_pixels == 0
+    }
+}
+
+impl Image {
+    /// Creates a fully transparent image
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            (width * height * 4) as usize,
+            "Pixel data doesn't match the image size!"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Decodes a PNG, converting it to 8-bit RGBA
+    pub fn from_png(bytes: &[u8]) -> Result<Self, png::DecodingError> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let (info, mut reader) = decoder.read_info()?;
+        let mut buffer = vec![0; info.buffer_size()];
+        reader.next_frame(&mut buffer)?;
+
+        let mut pixels = Vec::with_capacity((info.width * info.height * 4) as usize);
+        match info.color_type {
+            png::ColorType::RGBA => pixels.extend_from_slice(&buffer),
+            png::ColorType::RGB => {
+                for rgb in buffer.chunks_exact(3) {
+                    pixels.extend_from_slice(&[rgb[0], rgb[1], rgb[2], 255]);
+                }
+            }
+            png::ColorType::GrayscaleAlpha => {
+                for gray_alpha in buffer.chunks_exact(2) {
+                    let gray = gray_alpha[0];
+                    pixels.extend_from_slice(&[gray, gray, gray, gray_alpha[1]]);
+                }
+            }
+            png::ColorType::Grayscale => {
+                for gray in buffer.iter() {
+                    pixels.extend_from_slice(&[*gray, *gray, *gray, 255]);
+                }
+            }
+            png::ColorType::Indexed => {
+                // Palettes are expanded to RGB(A) by the EXPAND transformation
+                unreachable!("Indexed PNGs should have been expanded!")
+            }
+        }
+
+        Ok(Self::from_rgba(info.width, info.height, pixels))
+    }
+
+    pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Self, png::DecodingError> {
+        let bytes = std::fs::read(path)?;
+        Self::from_png(&bytes)
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut bytes = Vec::new();
+        self.write_png(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), png::EncodingError> {
+        let file = std::fs::File::create(path)?;
+        self.write_png(BufWriter::new(file))
+    }
+
+    fn write_png<W: std::io::Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)
+    }
+
+    /// Returns the pixel at the given coordinates, or a transparent pixel if they are out of bounds
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        if x >= self.width || y >= self.height {
+            return [0; 4];
+        }
+        let index = ((y * self.width + x) * 4) as usize;
+        [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+            self.pixels[index + 3],
+        ]
+    }
+
+    /// Bilinearly samples the image at the given position in pixels, with components between 0.0 and 1.0
+    ///
+    /// Texel centers are at half pixel offsets. Positions outside of the image are clamped to its edges.
+    pub(crate) fn sample(&self, x: f32, y: f32) -> [f32; 4] {
+        if self.width == 0 || self.height == 0 {
+            return [0.0; 4];
+        }
+
+        let x = (x - 0.5).clamp(0.0, (self.width - 1) as f32);
+        let y = (y - 0.5).clamp(0.0, (self.height - 1) as f32);
+        let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+
+        let texel = |x: u32, y: u32| {
+            let pixel = self.get_pixel(x, y);
+            [
+                pixel[0] as f32 / 255.0,
+                pixel[1] as f32 / 255.0,
+                pixel[2] as f32 / 255.0,
+                pixel[3] as f32 / 255.0,
+            ]
+        };
+        let (top_left, top_right) = (texel(x0, y0), texel(x1, y0));
+        let (bottom_left, bottom_right) = (texel(x0, y1), texel(x1, y1));
+
+        let mut color = [0.0; 4];
+        for (i, channel) in color.iter_mut().enumerate() {
+            let top = top_left[i] + (top_right[i] - top_left[i]) * tx;
+            let bottom = bottom_left[i] + (bottom_right[i] - bottom_left[i]) * tx;
+            *channel = top + (bottom - top) * ty;
+        }
+        color
+    }
+
+    /// Compares this image with another one, pixel by pixel
+    ///
+    /// Pixels with every channel within `tolerance` of the other image are considered equal. When the sizes
+    /// differ, pixels outside of the smaller image are compared against transparent pixels.
+    pub fn diff(&self, other: &Image, tolerance: u8) -> ImageDiff {
+        let width = self.width.max(other.width);
+        let height = self.height.max(other.height);
+        let mut image = Image::new(width, height);
+        let mut different_pixels = 0;
+        let mut max_difference = 0;
+
+        for y in 0..height {
+            for x in 0..width {
+                let a = self.get_pixel(x, y);
+                let b = other.get_pixel(x, y);
+                let difference = a
+                    .iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| (*a as i16 - *b as i16).unsigned_abs() as u8)
+                    .max()
+                    .unwrap_or(0);
+                max_difference = max_difference.max(difference);
+
+                let index = ((y * width + x) * 4) as usize;
+                let pixel = if difference > tolerance {
+                    different_pixels += 1;
+                    [255, 0, 0, 255]
+                } else {
+                    // Keep a faded copy of the image for context
+                    [a[0] / 4, a[1] / 4, a[2] / 4, 255]
+                };
+                image.pixels[index..index + 4].copy_from_slice(&pixel);
+            }
+        }
+
+        ImageDiff {
+            different_pixels,
+            max_difference,
+            image,
+        }
+    }
+}
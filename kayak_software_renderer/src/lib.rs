@@ -0,0 +1,19 @@
+//! A CPU renderer for kayak's render primitives
+//!
+//! This renders the output of `WidgetManager::build_render_primitives` into an RGBA [Image] without a GPU,
+//! which makes it possible to take screenshots of a UI and compare them in headless tests.
+//!
+//! ```ignore
+//! let mut renderer = SoftwareRenderer::new(800, 600);
+//! renderer.load_font(0, include_bytes!("roboto.kayak_font"), include_bytes!("roboto.png"))?;
+//!
+//! let image = renderer.render(&context.widget_manager.build_render_primitives());
+//! image.save_png("screenshot.png")?;
+//! ```
+
+mod canvas;
+mod image;
+mod renderer;
+
+pub use image::{Image, ImageDiff};
+pub use renderer::{FontAtlas, SoftwareRenderer};
@@ -0,0 +1,593 @@
+use std::collections::HashMap;
+
+use kayak_core::{
+    color::Color,
+    layout_cache::{Rect, Space},
+    render_primitive::RenderPrimitive,
+    Affine,
+};
+use kayak_font::{Alignment, CoordinateSystem, KayakFont, Origin, Sdf};
+
+use crate::{
+    canvas::{Bounds, Canvas},
+    Image,
+};
+
+/// A font along with the image of its atlas
+pub struct FontAtlas {
+    pub font: KayakFont,
+    pub atlas: Image,
+}
+
+/// Rasterizes render primitives on the CPU
+///
+/// Fonts and images are referred to by the same ids as in `RenderCommand::Text` and `RenderCommand::Image`, so
+/// they need to be registered under the ids the UI was built with.
+pub struct SoftwareRenderer {
+    width: u32,
+    height: u32,
+    clear_color: Color,
+    fonts: HashMap<u16, FontAtlas>,
+    images: HashMap<u16, Image>,
+}
+
+impl SoftwareRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            clear_color: Color::TRANSPARENT,
+            fonts: HashMap::new(),
+            images: HashMap::new(),
+        }
+    }
+
+    /// Sets the color the image is cleared with before rendering (transparent by default)
+    pub fn with_clear_color(mut self, clear_color: Color) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    pub fn add_font(&mut self, id: u16, font: KayakFont, atlas: Image) {
+        self.fonts.insert(id, FontAtlas { font, atlas });
+    }
+
+    /// Loads a font from the contents of a `.kayak_font` file and its PNG atlas
+    pub fn load_font(
+        &mut self,
+        id: u16,
+        kayak_font: &[u8],
+        atlas_png: &[u8],
+    ) -> Result<(), png::DecodingError> {
+        let font = KayakFont::from_sdf(Sdf::from_bytes(kayak_font));
+        let atlas = Image::from_png(atlas_png)?;
+        self.add_font(id, font, atlas);
+        Ok(())
+    }
+
+    pub fn add_image(&mut self, id: u16, image: Image) {
+        self.images.insert(id, image);
+    }
+
+    /// Renders the given primitives to an image
+    ///
+    /// Primitives are drawn in z-index order, like the GPU renderer does. Each primitive is clipped by the clip
+    /// that was active at its position in the list: a clip applies until an enclosing clip is applied again,
+    /// and nested clips are intersected with their enclosing clip.
+    pub fn render(&self, primitives: &[RenderPrimitive]) -> Image {
+        let mut canvas = Canvas::new(self.width, self.height, self.clear_color);
+        let viewport = canvas.bounds();
+
+        let mut clip_stack: Vec<(Rect, Bounds)> = Vec::new();
+        let mut draws = Vec::new();
+        for primitive in primitives {
+            match primitive {
+                RenderPrimitive::Empty => {}
+                RenderPrimitive::Clip { layout } => {
+                    let same_area = |rect: &Rect| {
+                        rect.posx == layout.posx
+                            && rect.posy == layout.posy
+                            && rect.width == layout.width
+                            && rect.height == layout.height
+                    };
+                    if let Some(index) = clip_stack.iter().position(|(rect, _)| same_area(rect)) {
+                        // An enclosing clip is applied again, so its children are done
+                        clip_stack.truncate(index + 1);
+                    } else {
+                        let parent = clip_stack
+                            .last()
+                            .map(|(_, bounds)| *bounds)
+                            .unwrap_or(viewport);
+                        clip_stack.push((*layout, parent.intersect(&layout.into())));
+                    }
+                }
+                _ => {
+                    let clip = clip_stack
+                        .last()
+                        .map(|(_, bounds)| *bounds)
+                        .unwrap_or(viewport);
+                    draws.push((primitive, clip));
+                }
+            }
+        }
+
+        // Stable, so primitives with the same z index keep their order
+        draws.sort_by(|(a, _), (b, _)| {
+            z_index(a)
+                .partial_cmp(&z_index(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        for (primitive, clip) in draws {
+            self.draw(&mut canvas, primitive, &clip);
+        }
+
+        canvas.into_image()
+    }
+
+    fn draw(&self, canvas: &mut Canvas, primitive: &RenderPrimitive, clip: &Bounds) {
+        match primitive {
+            RenderPrimitive::Quad {
+                layout,
+                background,
+                border_radius,
+                border,
+                border_color,
+            } => {
+                canvas.fill_rounded_rect(
+                    &layout.into(),
+                    *border_radius,
+                    background,
+                    *border,
+                    border_color,
+                    clip,
+                );
+            }
+            RenderPrimitive::BoxShadow {
+                layout,
+                border_radius,
+                box_shadow,
+            } => {
+                canvas.draw_box_shadow(&layout.into(), *border_radius, box_shadow, clip);
+            }
+            RenderPrimitive::Text {
+                layout,
+                color,
+                size,
+                content,
+                font,
+            } => {
+                if let Some(font_atlas) = self.fonts.get(font) {
+                    Self::draw_text(canvas, font_atlas, layout, color, *size, content, clip);
+                }
+            }
+            RenderPrimitive::Image {
+                layout,
+                handle,
+                opacity,
+            } => {
+                if let Some(image) = self.images.get(handle) {
+                    let source = Bounds::new(0.0, 0.0, image.width as f32, image.height as f32);
+                    canvas.draw_image(image, &source, &layout.into(), *opacity, clip);
+                }
+            }
+            RenderPrimitive::NinePatch {
+                border,
+                layout,
+                handle,
+                opacity,
+            } => {
+                if let Some(image) = self.images.get(handle) {
+                    Self::draw_nine_patch(canvas, image, layout, border, *opacity, clip);
+                }
+            }
+            RenderPrimitive::Transformed {
+                transform,
+                primitive,
+            } => self.draw_transformed(canvas, transform, primitive, clip),
+            RenderPrimitive::Empty | RenderPrimitive::Clip { .. } => {}
+        }
+    }
+
+    /// Draws a primitive in its untransformed layout onto a layer, then draws the layer transformed
+    fn draw_transformed(
+        &self,
+        canvas: &mut Canvas,
+        transform: &Affine,
+        primitive: &RenderPrimitive,
+        clip: &Bounds,
+    ) {
+        let (layout, bounds) = match primitive {
+            RenderPrimitive::BoxShadow {
+                layout, box_shadow, ..
+            } => (*layout, box_shadow.bounds(layout)),
+            RenderPrimitive::Quad { layout, .. }
+            | RenderPrimitive::Text { layout, .. }
+            | RenderPrimitive::Image { layout, .. }
+            | RenderPrimitive::NinePatch { layout, .. } => (*layout, *layout),
+            _ => return,
+        };
+
+        // The layer covers the area drawn by the primitive, with a pixel of margin for antialiasing
+        let (origin_x, origin_y) = (bounds.posx.floor() - 1.0, bounds.posy.floor() - 1.0);
+        let width = (bounds.posx + bounds.width).ceil() + 1.0 - origin_x;
+        let height = (bounds.posy + bounds.height).ceil() + 1.0 - origin_y;
+        let mut layer = Canvas::new(width as u32, height as u32, Color::TRANSPARENT);
+
+        let mut local_primitive = primitive.clone();
+        local_primitive.set_layout(Rect {
+            posx: layout.posx - origin_x,
+            posy: layout.posy - origin_y,
+            ..layout
+        });
+        let layer_bounds = layer.bounds();
+        self.draw(&mut layer, &local_primitive, &layer_bounds);
+
+        canvas.draw_layer(
+            &layer,
+            &Affine::translation(origin_x, origin_y).then(transform),
+            clip,
+        );
+    }
+
+    fn draw_text(
+        canvas: &mut Canvas,
+        font_atlas: &FontAtlas,
+        layout: &Rect,
+        color: &Color,
+        font_size: f32,
+        content: &str,
+        clip: &Bounds,
+    ) {
+        let font = &font_atlas.font;
+        let sdf = &font.sdf;
+        let line_height = font_size * 1.2;
+        let font_ratio = font_size / sdf.atlas.size;
+        let screen_px_range = sdf.atlas.distance_range * font_ratio;
+        let atlas_height = sdf.atlas.height as f32;
+
+        let glyph_layouts = font.get_layout(
+            CoordinateSystem::PositiveYDown,
+            Alignment::Start,
+            (layout.posx, layout.posy + line_height),
+            (layout.width, layout.height),
+            &content.to_string(),
+            line_height,
+            font_size,
+        );
+
+        for glyph_layout in glyph_layouts {
+            let atlas_bounds = match sdf
+                .glyphs
+                .iter()
+                .find(|glyph| glyph.unicode == glyph_layout.content)
+                .and_then(|glyph| glyph.atlas_bounds)
+            {
+                Some(atlas_bounds) => atlas_bounds,
+                None => continue,
+            };
+
+            let (top, bottom) = match sdf.atlas.y_origin {
+                Origin::Bottom => (
+                    atlas_height - atlas_bounds.top,
+                    atlas_height - atlas_bounds.bottom,
+                ),
+                _ => (atlas_bounds.top, atlas_bounds.bottom),
+            };
+            let glyph = Bounds {
+                min_x: atlas_bounds.left,
+                min_y: top.min(bottom),
+                max_x: atlas_bounds.right,
+                max_y: top.max(bottom),
+            };
+
+            // Glyphs are placed at the top left of their layout rect, which is sized for the largest glyph
+            let destination = Bounds::new(
+                glyph_layout.position.0,
+                glyph_layout.position.1,
+                glyph.width() * font_ratio,
+                glyph.height() * font_ratio,
+            );
+
+            canvas.draw_msdf_glyph(
+                &font_atlas.atlas,
+                &glyph,
+                &destination,
+                screen_px_range,
+                color,
+                clip,
+            );
+        }
+    }
+
+    fn draw_nine_patch(
+        canvas: &mut Canvas,
+        image: &Image,
+        layout: &Rect,
+        border: &Space,
+        opacity: f32,
+        clip: &Bounds,
+    ) {
+        let (image_width, image_height) = (image.width as f32, image.height as f32);
+
+        // The source and destination edges of the three columns and rows
+        let source_columns = [0.0, border.left, image_width - border.right, image_width];
+        let source_rows = [0.0, border.top, image_height - border.bottom, image_height];
+        let columns = [
+            layout.posx,
+            layout.posx + border.left,
+            layout.posx + layout.width - border.right,
+            layout.posx + layout.width,
+        ];
+        let rows = [
+            layout.posy,
+            layout.posy + border.top,
+            layout.posy + layout.height - border.bottom,
+            layout.posy + layout.height,
+        ];
+
+        for row in 0..3 {
+            for column in 0..3 {
+                let source = Bounds {
+                    min_x: source_columns[column],
+                    min_y: source_rows[row],
+                    max_x: source_columns[column + 1],
+                    max_y: source_rows[row + 1],
+                };
+                let destination = Bounds {
+                    min_x: columns[column],
+                    min_y: rows[row],
+                    max_x: columns[column + 1],
+                    max_y: rows[row + 1],
+                };
+                canvas.draw_image(image, &source, &destination, opacity, clip);
+            }
+        }
+    }
+}
+
+fn z_index(primitive: &RenderPrimitive) -> f32 {
+    match primitive {
+        RenderPrimitive::Clip { layout }
+        | RenderPrimitive::Quad { layout, .. }
+        | RenderPrimitive::BoxShadow { layout, .. }
+        | RenderPrimitive::Text { layout, .. }
+        | RenderPrimitive::Image { layout, .. }
+        | RenderPrimitive::NinePatch { layout, .. } => layout.z_index,
+        RenderPrimitive::Transformed { primitive, .. } => z_index(primitive),
+        RenderPrimitive::Empty => 0.0,
+    }
+}
+
+#[test]
+fn test_render_quads() {
+    use kayak_core::Background;
+
+    let layout = |posx: f32, posy: f32, width: f32, height: f32, z_index: f32| Rect {
+        posx,
+        posy,
+        width,
+        height,
+        z_index,
+    };
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+
+    let renderer = SoftwareRenderer::new(100, 100).with_clear_color(Color::BLACK);
+    let image = renderer.render(&[
+        RenderPrimitive::Clip {
+            layout: layout(0.0, 0.0, 50.0, 100.0, 0.0),
+        },
+        // Drawn on top of the blue quad since its z index is higher
+        RenderPrimitive::Quad {
+            layout: layout(0.0, 0.0, 100.0, 100.0, 2.0),
+            background: Background::Color(red),
+            border_radius: (10.0, 10.0, 10.0, 10.0),
+            border: (0.0, 0.0, 0.0, 0.0),
+            border_color: Color::TRANSPARENT,
+        },
+        RenderPrimitive::Quad {
+            layout: layout(20.0, 20.0, 60.0, 60.0, 1.0),
+            background: Background::Color(blue),
+            border_radius: (0.0, 0.0, 0.0, 0.0),
+            border: (0.0, 0.0, 0.0, 0.0),
+            border_color: Color::TRANSPARENT,
+        },
+    ]);
+
+    assert_eq!(image.get_pixel(25, 25), [255, 0, 0, 255]);
+    // Rounded corner
+    assert_eq!(image.get_pixel(0, 0), [0, 0, 0, 255]);
+    // Clipped
+    assert_eq!(image.get_pixel(75, 50), [0, 0, 0, 255]);
+
+    let png = image.to_png().unwrap();
+    assert!(Image::from_png(&png).unwrap().diff(&image, 0).is_empty());
+}
+
+#[test]
+fn test_render_borders() {
+    use kayak_core::Background;
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+
+    let renderer = SoftwareRenderer::new(100, 100).with_clear_color(Color::BLACK);
+    let image = renderer.render(&[RenderPrimitive::Quad {
+        layout: Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width: 100.0,
+            height: 100.0,
+            z_index: 0.0,
+        },
+        background: Background::Color(red),
+        border_radius: (20.0, 20.0, 20.0, 20.0),
+        border: (10.0, 0.0, 0.0, 5.0),
+        border_color: blue,
+    }]);
+
+    // Top and left borders
+    assert_eq!(image.get_pixel(50, 5), [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(2, 50), [0, 0, 255, 255]);
+    // No border on the right and bottom sides
+    assert_eq!(image.get_pixel(98, 50), [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(50, 98), [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(50, 50), [255, 0, 0, 255]);
+    // The border follows the rounded corner
+    assert_eq!(image.get_pixel(0, 0), [0, 0, 0, 255]);
+}
+
+#[test]
+fn test_render_box_shadows() {
+    use kayak_core::{Background, BoxShadow};
+
+    let layout = |posx: f32, posy: f32, width: f32, height: f32, z_index: f32| Rect {
+        posx,
+        posy,
+        width,
+        height,
+        z_index,
+    };
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+    let quad = |layout: Rect, color: Color| RenderPrimitive::Quad {
+        layout,
+        background: Background::Color(color),
+        border_radius: (0.0, 0.0, 0.0, 0.0),
+        border: (0.0, 0.0, 0.0, 0.0),
+        border_color: Color::TRANSPARENT,
+    };
+
+    let renderer = SoftwareRenderer::new(100, 100).with_clear_color(Color::WHITE);
+    let image = renderer.render(&[
+        quad(layout(30.0, 30.0, 40.0, 40.0, 1.0), red),
+        RenderPrimitive::BoxShadow {
+            layout: layout(30.0, 30.0, 40.0, 40.0, 0.99),
+            border_radius: (0.0, 0.0, 0.0, 0.0),
+            box_shadow: BoxShadow {
+                offset: (10.0, 10.0),
+                color: Color::BLACK,
+                ..BoxShadow::default()
+            },
+        },
+        quad(layout(0.0, 0.0, 20.0, 20.0, 1.0), blue),
+        RenderPrimitive::BoxShadow {
+            layout: layout(0.0, 0.0, 20.0, 20.0, 1.01),
+            border_radius: (0.0, 0.0, 0.0, 0.0),
+            box_shadow: BoxShadow {
+                spread: 5.0,
+                color: Color::BLACK,
+                inset: true,
+                ..BoxShadow::default()
+            },
+        },
+    ]);
+
+    // Drop shadow, behind the quad
+    assert_eq!(image.get_pixel(75, 75), [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(50, 50), [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(25, 75), [255, 255, 255, 255]);
+    // Inset shadow, over the quad
+    assert_eq!(image.get_pixel(2, 10), [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(10, 10), [0, 0, 255, 255]);
+}
+
+#[test]
+fn test_render_gradients() {
+    use kayak_core::{Background, ColorStop};
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+
+    let renderer = SoftwareRenderer::new(100, 10);
+    let image = renderer.render(&[RenderPrimitive::Quad {
+        layout: Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width: 100.0,
+            height: 10.0,
+            z_index: 0.0,
+        },
+        background: Background::LinearGradient {
+            angle: 90.0,
+            stops: vec![ColorStop::new(red, 0.2), ColorStop::new(blue, 0.8)],
+        },
+        border_radius: (0.0, 0.0, 0.0, 0.0),
+        border: (0.0, 0.0, 0.0, 0.0),
+        border_color: Color::TRANSPARENT,
+    }]);
+
+    // Solid before the first stop and after the last one
+    assert_eq!(image.get_pixel(5, 5), [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(95, 5), [0, 0, 255, 255]);
+    let middle = image.get_pixel(49, 5);
+    assert!(middle[0] > 100 && middle[2] > 100);
+}
+
+#[test]
+fn test_render_transforms() {
+    use kayak_core::{Background, Transform};
+
+    let layout = Rect {
+        posx: 30.0,
+        posy: 40.0,
+        width: 40.0,
+        height: 20.0,
+        z_index: 0.0,
+    };
+    let renderer = SoftwareRenderer::new(100, 100).with_clear_color(Color::BLACK);
+    let image = renderer.render(&[RenderPrimitive::Transformed {
+        transform: Transform {
+            rotation: 90.0,
+            ..Transform::default()
+        }
+        .to_affine(&layout),
+        primitive: Box::new(RenderPrimitive::Quad {
+            layout,
+            background: Background::Color(Color::new(1.0, 0.0, 0.0, 1.0)),
+            border_radius: (0.0, 0.0, 0.0, 0.0),
+            border: (0.0, 0.0, 0.0, 0.0),
+            border_color: Color::TRANSPARENT,
+        }),
+    }]);
+
+    // Rotated around its center, the quad is now taller than wide
+    assert_eq!(image.get_pixel(50, 50), [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(50, 35), [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(35, 50), [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(50, 75), [0, 0, 0, 255]);
+}
+
+#[test]
+fn test_render_text() {
+    let mut renderer = SoftwareRenderer::new(200, 50);
+    renderer
+        .load_font(
+            0,
+            include_bytes!("../../kayak_font/assets/roboto.kayak_font"),
+            include_bytes!("../../assets/roboto.png"),
+        )
+        .unwrap();
+
+    let image = renderer.render(&[RenderPrimitive::Text {
+        layout: Rect {
+            posx: 0.0,
+            posy: 0.0,
+            width: 200.0,
+            height: 50.0,
+            z_index: 0.0,
+        },
+        color: Color::WHITE,
+        size: 32.0,
+        content: "Hello".to_string(),
+        font: 0,
+    }]);
+
+    let covered_pixels = image
+        .pixels
+        .chunks_exact(4)
+        .filter(|pixel| pixel[3] > 128)
+        .count();
+    assert!(covered_pixels > 100);
+}
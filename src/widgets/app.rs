@@ -6,7 +6,7 @@ use crate::core::{
     widget, Children,
 };
 
-use crate::widgets::Clip;
+use crate::widgets::{use_default_stylesheet, Clip};
 
 #[widget]
 pub fn App(children: Children) {
@@ -15,6 +15,8 @@ pub fn App(children: Children) {
         ..styles.clone().unwrap_or_default()
     });
 
+    use_default_stylesheet(context);
+
     #[cfg(feature = "bevy_renderer")]
     {
         use crate::bevy::WindowSize;
@@ -1,29 +1,23 @@
 use crate::core::{
-    color::Color,
     render_command::RenderCommand,
     rsx,
-    styles::{Style, StyleProp, Units},
-    widget, Children, Fragment,
+    styles::{Style, StyleProp},
+    widget, Children, CursorIcon, Fragment,
 };
 
-#[widget]
+use crate::widgets::use_default_stylesheet;
+
+#[widget(focusable, class = "button")]
 pub fn Button(children: Children, styles: Option<Style>) {
+    use_default_stylesheet(context);
     let base_styles = styles.clone().unwrap_or_default();
     *styles = Some(Style {
         render_command: StyleProp::Value(RenderCommand::Quad),
-        border_radius: StyleProp::Value((5.0, 5.0, 5.0, 5.0)),
-        height: if base_styles.height == StyleProp::Default {
-            StyleProp::Value(Units::Pixels(45.0))
-        } else {
-            base_styles.height
-        },
-        background_color: if matches!(base_styles.background_color, StyleProp::Default) {
-            StyleProp::Value(Color::new(0.0781, 0.0898, 0.101, 1.0))
+        cursor: if matches!(base_styles.cursor, StyleProp::Default | StyleProp::Inherit) {
+            StyleProp::Value(CursorIcon::Pointer)
         } else {
-            base_styles.background_color
+            base_styles.cursor
         },
-        padding_left: StyleProp::Value(Units::Stretch(1.0)),
-        padding_right: StyleProp::Value(Units::Stretch(1.0)),
         ..base_styles
     });
     rsx! {
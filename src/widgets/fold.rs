@@ -36,7 +36,7 @@ use crate::widgets::{Background, Clip, If, Text};
 ///     </Fold>
 /// }
 /// ```
-#[widget]
+#[widget(no_memo)]
 pub fn Fold(label: String, children: Children, open: Option<bool>, on_change: Option<Handler<bool>>, default_open: bool) {
 
     // === State === //
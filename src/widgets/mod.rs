@@ -7,6 +7,7 @@ mod if_element;
 mod image;
 mod text_box;
 mod nine_patch;
+mod stylesheet;
 mod text;
 mod window;
 mod fold;
@@ -22,6 +23,7 @@ pub use if_element::*;
 pub use image::*;
 pub use text_box::*;
 pub use nine_patch::*;
+pub use stylesheet::*;
 pub use text::*;
 pub use tooltip::*;
 pub use window::*;
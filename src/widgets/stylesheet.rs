@@ -0,0 +1,107 @@
+use crate::core::{
+    color::Color,
+    styles::{Style, StyleProp, Units},
+    ClassStyle, KayakContext, StyleSheet,
+};
+
+/// Returns the stylesheet with the classes the built-in widgets are styled with
+///
+/// The built-in widgets set it as the [default stylesheet](KayakContext::set_default_stylesheet) unless one was set
+/// already. To restyle them, set a stylesheet with classes of the same names, which are applied on top of these
+/// ones:
+///
+/// ```ignore
+/// context.set_stylesheet(StyleSheet::new().with_class(
+///     "button",
+///     ClassStyle::new(Style { ... }).with_hover(Style { ... }),
+/// ));
+/// ```
+///
+/// | Class                  | Widget                                         |
+/// |------------------------|------------------------------------------------|
+/// | `button`               | [Button](crate::widgets::Button)               |
+/// | `window`               | [Window](crate::widgets::Window)               |
+/// | `window-clip`          | The area within the window's border            |
+/// | `window-title`         | The window's title bar                         |
+/// | `window-content`       | The element holding the window's content       |
+/// | `text-box`             | [TextBox](crate::widgets::TextBox) (not set)   |
+/// | `text-box-background`  | The background of the text box                 |
+/// | `text-box-placeholder` | The text of an empty text box                  |
+pub fn default_stylesheet() -> StyleSheet {
+    StyleSheet::new()
+        .with_class(
+            "button",
+            ClassStyle::new(Style {
+                background_color: StyleProp::Value(Color::new(0.0781, 0.0898, 0.101, 1.0)),
+                border_radius: StyleProp::Value((5.0, 5.0, 5.0, 5.0)),
+                height: StyleProp::Value(Units::Pixels(45.0)),
+                padding_left: StyleProp::Value(Units::Stretch(1.0)),
+                padding_right: StyleProp::Value(Units::Stretch(1.0)),
+                ..Style::default()
+            }),
+        )
+        .with_class(
+            "window",
+            ClassStyle::new(Style {
+                background_color: StyleProp::Value(Color::new(0.125, 0.125, 0.125, 1.0)),
+                border_radius: StyleProp::Value((5.0, 5.0, 5.0, 5.0)),
+                ..Style::default()
+            }),
+        )
+        .with_class(
+            "window-clip",
+            ClassStyle::new(Style {
+                padding_left: StyleProp::Value(Units::Pixels(5.0)),
+                padding_right: StyleProp::Value(Units::Pixels(5.0)),
+                padding_top: StyleProp::Value(Units::Pixels(5.0)),
+                padding_bottom: StyleProp::Value(Units::Pixels(5.0)),
+                ..Style::default()
+            }),
+        )
+        .with_class(
+            "window-title",
+            ClassStyle::new(Style {
+                background_color: StyleProp::Value(Color::new(0.0781, 0.0898, 0.101, 1.0)),
+                border_radius: StyleProp::Value((5.0, 0.0, 0.0, 5.0)),
+                height: StyleProp::Value(Units::Pixels(24.0)),
+                padding_left: StyleProp::Value(Units::Pixels(5.0)),
+                padding_top: StyleProp::Value(Units::Stretch(1.0)),
+                padding_bottom: StyleProp::Value(Units::Stretch(1.0)),
+                ..Style::default()
+            }),
+        )
+        .with_class(
+            "window-content",
+            ClassStyle::new(Style {
+                padding_left: StyleProp::Value(Units::Pixels(10.0)),
+                padding_right: StyleProp::Value(Units::Pixels(10.0)),
+                padding_top: StyleProp::Value(Units::Pixels(10.0)),
+                padding_bottom: StyleProp::Value(Units::Pixels(10.0)),
+                ..Style::default()
+            }),
+        )
+        .with_class(
+            "text-box-background",
+            ClassStyle::new(Style {
+                background_color: StyleProp::Value(Color::new(0.176, 0.196, 0.215, 1.0)),
+                border_radius: StyleProp::Value((5.0, 5.0, 5.0, 5.0)),
+                padding_left: StyleProp::Value(Units::Pixels(5.0)),
+                padding_right: StyleProp::Value(Units::Pixels(5.0)),
+                ..Style::default()
+            }),
+        )
+        .with_class(
+            "text-box-placeholder",
+            ClassStyle::new(Style {
+                color: StyleProp::Value(Color::new(0.5, 0.5, 0.5, 1.0)),
+                ..Style::default()
+            }),
+        )
+}
+
+/// Sets the default stylesheet the built-in widgets are styled with, unless one was set already
+pub(crate) fn use_default_stylesheet(context: &mut KayakContext) {
+    if context.default_stylesheet().is_empty() {
+        context.set_default_stylesheet(default_stylesheet());
+    }
+}
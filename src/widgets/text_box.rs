@@ -2,11 +2,11 @@ use crate::core::{
     render_command::RenderCommand,
     rsx,
     styles::{Style, StyleProp, Units},
-    widget, Bound, Color, EventType, MutableBound, OnEvent,
+    widget, Bound, CursorIcon, EventType, MutableBound, OnEvent,
 };
 use std::sync::{Arc, RwLock};
 
-use crate::widgets::{Background, Clip, Text};
+use crate::widgets::{use_default_stylesheet, Background, Clip, Text};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ChangeEvent {
@@ -37,12 +37,21 @@ impl std::fmt::Debug for OnChange {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Focus(pub bool);
 
-#[widget(focusable)]
+#[widget(focusable, no_memo, class = "text-box")]
 pub fn TextBox(value: String, on_change: Option<OnChange>, placeholder: Option<String>) {
+    use_default_stylesheet(context);
     let current_styles = styles.clone().unwrap_or_default();
     *styles = Some(Style {
         render_command: StyleProp::Value(RenderCommand::Layout),
         height: StyleProp::Value(Units::Pixels(26.0)),
+        cursor: if matches!(
+            current_styles.cursor,
+            StyleProp::Default | StyleProp::Inherit
+        ) {
+            StyleProp::Value(CursorIcon::Text)
+        } else {
+            current_styles.cursor.clone()
+        },
         top: if matches!(current_styles.top, StyleProp::Value { .. }) {
             current_styles.top.clone()
         } else {
@@ -57,11 +66,7 @@ pub fn TextBox(value: String, on_change: Option<OnChange>, placeholder: Option<S
     });
 
     let background_styles = Style {
-        background_color: StyleProp::Value(Color::new(0.176, 0.196, 0.215, 1.0)),
-        border_radius: StyleProp::Value((5.0, 5.0, 5.0, 5.0)),
         height: StyleProp::Value(Units::Pixels(26.0)),
-        padding_left: StyleProp::Value(Units::Pixels(5.0)),
-        padding_right: StyleProp::Value(Units::Pixels(5.0)),
         ..styles.clone().unwrap_or_default()
     };
 
@@ -96,16 +101,14 @@ pub fn TextBox(value: String, on_change: Option<OnChange>, placeholder: Option<S
         _ => {}
     }));
 
-    let text_styles = if value.is_empty() || (has_focus.get().0 && value.is_empty()) {
-        Style {
-            color: StyleProp::Value(Color::new(0.5, 0.5, 0.5, 1.0)),
-            ..Style::default()
-        }
+    let (text_styles, text_class) = if value.is_empty() || (has_focus.get().0 && value.is_empty()) {
+        (Style::default(), Some("text-box-placeholder".to_string()))
     } else {
-        Style {
+        let text_styles = Style {
             color: styles.clone().unwrap_or_default().color,
             ..Style::default()
-        }
+        };
+        (text_styles, None)
     };
 
     let value = if value.is_empty() {
@@ -115,12 +118,13 @@ pub fn TextBox(value: String, on_change: Option<OnChange>, placeholder: Option<S
     };
 
     rsx! {
-        <Background styles={Some(background_styles)}>
+        <Background styles={Some(background_styles)} class={Some("text-box-background".to_string())}>
             <Clip>
                 <Text
                     content={value}
                     size={14.0}
                     styles={Some(text_styles)}
+                    class={text_class}
                 />
             </Clip>
         </Background>
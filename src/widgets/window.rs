@@ -1,14 +1,13 @@
 use crate::core::{
-    color::Color,
     render_command::RenderCommand,
     rsx,
     styles::{PositionType, Style, StyleProp, Units},
     widget, Children,
 };
 
-use crate::widgets::{Background, Clip, Element, Text};
+use crate::widgets::{use_default_stylesheet, Background, Clip, Element, Text};
 
-#[widget]
+#[widget(class = "window")]
 pub fn Window(
     children: Children,
     styles: Option<Style>,
@@ -16,9 +15,8 @@ pub fn Window(
     size: (f32, f32),
     title: String,
 ) {
+    use_default_stylesheet(context);
     *styles = Some(Style {
-        background_color: StyleProp::Value(Color::new(0.125, 0.125, 0.125, 1.0)),
-        border_radius: StyleProp::Value((5.0, 5.0, 5.0, 5.0)),
         render_command: StyleProp::Value(RenderCommand::Quad),
         position_type: StyleProp::Value(PositionType::SelfDirected),
         left: StyleProp::Value(Units::Pixels(position.0)),
@@ -31,10 +29,6 @@ pub fn Window(
     });
 
     let clip_styles = Style {
-        padding_left: StyleProp::Value(Units::Pixels(5.0)),
-        padding_right: StyleProp::Value(Units::Pixels(5.0)),
-        padding_top: StyleProp::Value(Units::Pixels(5.0)),
-        padding_bottom: StyleProp::Value(Units::Pixels(5.0)),
         width: StyleProp::Value(Units::Pixels(size.0)),
         height: StyleProp::Value(Units::Pixels(size.1)),
         max_width: StyleProp::Value(Units::Pixels(size.0)),
@@ -43,16 +37,10 @@ pub fn Window(
     };
 
     let title_background_styles = Style {
-        background_color: StyleProp::Value(Color::new(0.0781, 0.0898, 0.101, 1.0)),
-        border_radius: StyleProp::Value((5.0, 0.0, 0.0, 5.0)),
-        height: StyleProp::Value(Units::Pixels(24.0)),
         left: StyleProp::Value(Units::Pixels(0.0)),
         right: StyleProp::Value(Units::Pixels(0.0)),
         top: StyleProp::Value(Units::Pixels(0.0)),
         bottom: StyleProp::Value(Units::Pixels(0.0)),
-        padding_left: StyleProp::Value(Units::Pixels(5.0)),
-        padding_top: StyleProp::Value(Units::Stretch(1.0)),
-        padding_bottom: StyleProp::Value(Units::Stretch(1.0)),
         ..Style::default()
     };
 
@@ -61,21 +49,13 @@ pub fn Window(
         ..Style::default()
     };
 
-    let content_styles = Style {
-        padding_left: StyleProp::Value(Units::Pixels(10.0)),
-        padding_right: StyleProp::Value(Units::Pixels(10.0)),
-        padding_top: StyleProp::Value(Units::Pixels(10.0)),
-        padding_bottom: StyleProp::Value(Units::Pixels(10.0)),
-        ..Style::default()
-    };
-
     let title = title.clone();
     rsx! {
-        <Clip styles={Some(clip_styles)}>
-            <Background styles={Some(title_background_styles)}>
+        <Clip styles={Some(clip_styles)} class={Some("window-clip".to_string())}>
+            <Background styles={Some(title_background_styles)} class={Some("window-title".to_string())}>
                 <Text styles={Some(title_text_styles)} size={16.0} content={title} />
             </Background>
-            <Element styles={Some(content_styles)}>
+            <Element class={Some("window-content".to_string())}>
                 {children}
             </Element>
         </Clip>
@@ -0,0 +1,1685 @@
+use kayak_ui::core::{
+    constructor, render, rsx,
+    styles::{Style, StyleProp, Units},
+    testing::TestContext,
+    use_effect, use_state, widget, Binding, Children, EventType, Handler, Index, OnEvent,
+    VecTracker,
+};
+use kayak_ui::widgets::{Background, Button, Element, If, Text};
+
+#[widget]
+fn Counter() {
+    let (count, set_count, ..) = use_state!(1.0f32);
+    let on_event = OnEvent::new(move |_, event| match event.event_type {
+        EventType::Click => set_count(count + 1.0),
+        _ => {}
+    });
+
+    let button_styles = Style {
+        width: StyleProp::Value(Units::Pixels(100.0)),
+        height: StyleProp::Value(Units::Pixels(50.0)),
+        ..Default::default()
+    };
+    let bar_styles = Style {
+        width: StyleProp::Value(Units::Pixels(10.0 * count)),
+        height: StyleProp::Value(Units::Pixels(10.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <>
+            <Button styles={Some(button_styles)} on_event={Some(on_event)} />
+            <Background styles={Some(bar_styles)} />
+        </>
+    }
+}
+
+#[test]
+fn test_click_updates_layout() {
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <Element>
+                <Counter />
+            </Element>
+        }
+    });
+
+    let root = ui.find_widget::<Element>().unwrap();
+    let root_layout = ui.get_layout(root).unwrap();
+    assert_eq!((root_layout.width, root_layout.height), (800.0, 600.0));
+
+    let bar = ui.find_widget_by_name("Background").unwrap();
+    assert_eq!(ui.get_layout(bar).unwrap().width, 10.0);
+
+    let button = ui.find_widget::<Button>().unwrap();
+    ui.click_widget(button);
+    ui.click_widget(button);
+
+    assert_eq!(ui.get_layout(bar).unwrap().width, 30.0);
+    assert!(!ui.build_render_primitives().is_empty());
+}
+
+#[test]
+fn test_capture_stops_event_before_target() {
+    use kayak_ui::core::EventPhase;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let clicks = Arc::new(AtomicUsize::new(0));
+    let button_clicks = clicks.clone();
+    let on_button_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::Click {
+            button_clicks.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    let on_modal_event = OnEvent::with_capture(|_, event| {
+        if event.event_type == EventType::MouseDown && event.phase == EventPhase::Capture {
+            event.stop_propagation();
+        }
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element on_event={Some(on_modal_event)}>
+                <Button styles={Some(button_styles)} on_event={Some(on_button_event)} />
+            </Element>
+        }
+    });
+
+    let button = ui.find_widget::<Button>().unwrap();
+    ui.click_widget(button);
+    assert_eq!(clicks.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_plain_handlers_skip_capture_phase() {
+    use kayak_ui::core::EventPhase;
+    use std::sync::{Arc, RwLock};
+
+    let phases = Arc::new(RwLock::new(Vec::new()));
+    let element_phases = phases.clone();
+    let on_element_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::Click {
+            element_phases.write().unwrap().push(event.phase);
+        }
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element on_event={Some(on_element_event)}>
+                <Button styles={Some(button_styles)} />
+            </Element>
+        }
+    });
+
+    let button = ui.find_widget::<Button>().unwrap();
+    ui.click_widget(button);
+    assert_eq!(*phases.read().unwrap(), vec![EventPhase::Bubble]);
+}
+
+#[test]
+fn test_right_click_with_modifiers() {
+    use kayak_ui::core::KeyCode;
+    use std::sync::{Arc, RwLock};
+
+    let received = Arc::new(RwLock::new(Vec::new()));
+    let button_received = received.clone();
+    let on_event = OnEvent::new(move |_, event| match event.event_type {
+        EventType::RightClick | EventType::Scroll { .. } => {
+            button_received
+                .write()
+                .unwrap()
+                .push((event.event_type.clone(), event.modifiers.shift));
+        }
+        _ => {}
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles)} on_event={Some(on_event)} />
+            </Element>
+        }
+    });
+
+    ui.key_down(KeyCode::LShift);
+    ui.right_click((50.0, 25.0));
+    ui.key_up(KeyCode::LShift);
+    ui.scroll(0.0, -20.0);
+    // Ignored, since a NaN scroll couldn't be told apart from the others
+    ui.scroll(f32::NAN, -20.0);
+
+    assert_eq!(
+        *received.read().unwrap(),
+        vec![
+            (EventType::RightClick, true),
+            (
+                EventType::Scroll {
+                    delta_x: 0.0,
+                    delta_y: -20.0
+                },
+                false
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_tab_focus_order() {
+    use kayak_ui::core::KeyCode;
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <Element>
+                <Button />
+                <Button tab_index={Some(-1)} />
+                <Button tab_index={Some(1)} />
+            </Element>
+        }
+    });
+
+    let buttons = ui.find_widgets::<Button>();
+    assert_eq!(ui.kayak_context.current_focus(), None);
+
+    ui.press_key(KeyCode::Tab);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[2]));
+    ui.press_key(KeyCode::Tab);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[0]));
+    ui.press_key(KeyCode::Tab);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[2]));
+
+    ui.key_down(KeyCode::LShift);
+    ui.press_key(KeyCode::Tab);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[0]));
+    ui.key_up(KeyCode::LShift);
+
+    // Skipped by Tab, but can still be focused programmatically
+    ui.kayak_context.set_focus(Some(buttons[1]));
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[1]));
+
+    // Widgets that aren't mounted can't be focused
+    ui.kayak_context.set_focus(Some(Index::from_raw_parts(
+        buttons[1].into_raw_parts().0,
+        1000,
+    )));
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[1]));
+
+    ui.kayak_context.set_focus(None);
+    assert_eq!(ui.kayak_context.current_focus(), None);
+}
+
+#[test]
+fn test_directional_navigation() {
+    use kayak_ui::core::{layout_cache::Rect, InputEvent, NavigationDirection};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let confirms = Arc::new(AtomicUsize::new(0));
+    let button_confirms = confirms.clone();
+    let on_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::Click {
+            button_confirms.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    // A 2x2 grid of buttons
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = |left: f32, top: f32| Style {
+            left: StyleProp::Value(Units::Pixels(left)),
+            top: StyleProp::Value(Units::Pixels(top)),
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            position_type: StyleProp::Value(kayak_ui::core::styles::PositionType::SelfDirected),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles(0.0, 0.0))} />
+                <Button styles={Some(button_styles(200.0, 0.0))} />
+                <Button styles={Some(button_styles(0.0, 100.0))} />
+                <Button styles={Some(button_styles(200.0, 100.0))} on_event={Some(on_event)} />
+            </Element>
+        }
+    });
+
+    let buttons = ui.find_widgets::<Button>();
+    let layouts: Vec<Rect> = buttons
+        .iter()
+        .map(|button| ui.get_layout(*button).unwrap())
+        .collect();
+    assert_eq!((layouts[3].posx, layouts[3].posy), (200.0, 100.0));
+
+    // Without a focus, the first widget is focused
+    ui.process_events(vec![InputEvent::Navigate(NavigationDirection::Down)]);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[0]));
+
+    ui.process_events(vec![InputEvent::Navigate(NavigationDirection::Down)]);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[2]));
+    ui.process_events(vec![InputEvent::Navigate(NavigationDirection::Right)]);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[3]));
+
+    // Nothing further right
+    ui.process_events(vec![InputEvent::Navigate(NavigationDirection::Right)]);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[3]));
+
+    ui.process_events(vec![InputEvent::Confirm]);
+    assert_eq!(confirms.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_drag_and_drop() {
+    use std::sync::{Arc, RwLock};
+
+    let received = Arc::new(RwLock::new(Vec::new()));
+    let source_received = received.clone();
+    let on_source_event = OnEvent::new(move |_, event| match event.event_type {
+        EventType::DragStart => {
+            event.set_drag_payload(42u32);
+            source_received
+                .write()
+                .unwrap()
+                .push("drag start".to_string());
+        }
+        EventType::DragEnd => source_received
+            .write()
+            .unwrap()
+            .push("drag end".to_string()),
+        EventType::Click => source_received.write().unwrap().push("click".to_string()),
+        _ => {}
+    });
+    let target_received = received.clone();
+    let on_target_event = OnEvent::new(move |_, event| match &event.event_type {
+        EventType::DragEnter => target_received.write().unwrap().push("enter".to_string()),
+        EventType::Drop { payload } => target_received
+            .write()
+            .unwrap()
+            .push(format!("drop {:?}", payload.get::<u32>())),
+        _ => {}
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let slot_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(100.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Background styles={Some(slot_styles.clone())} on_event={Some(on_source_event)} />
+                <Background styles={Some(slot_styles)} on_event={Some(on_target_event)} />
+            </Element>
+        }
+    });
+
+    let slots = ui.find_widgets_by_name("Background");
+    let target = ui.get_layout(slots[1]).unwrap();
+    ui.drag((50.0, 50.0), (target.posx + 50.0, target.posy + 50.0));
+
+    assert_eq!(
+        *received.read().unwrap(),
+        vec!["drag start", "enter", "drop Some(42)", "drag end"]
+    );
+}
+
+#[test]
+fn test_pointer_capture() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mouse_ups = Arc::new(AtomicUsize::new(0));
+    let thumb_mouse_ups = mouse_ups.clone();
+    let on_event = OnEvent::new(move |context, event| match event.event_type {
+        EventType::MouseDown => context.capture_pointer(event.target),
+        EventType::MouseUp => {
+            thumb_mouse_ups.fetch_add(1, Ordering::SeqCst);
+            context.release_pointer();
+        }
+        _ => {}
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let thumb_styles = Style {
+            width: StyleProp::Value(Units::Pixels(20.0)),
+            height: StyleProp::Value(Units::Pixels(20.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Background styles={Some(thumb_styles)} on_event={Some(on_event)} />
+            </Element>
+        }
+    });
+
+    // The mouse is released far outside of the thumb
+    ui.drag((10.0, 10.0), (400.0, 10.0));
+    assert_eq!(mouse_ups.load(Ordering::SeqCst), 1);
+    assert_eq!(ui.kayak_context.pointer_capture(), None);
+
+    // Without a capture, the release doesn't reach the thumb
+    ui.click((400.0, 10.0));
+    assert_eq!(mouse_ups.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_timed_events() {
+    use std::sync::{Arc, RwLock};
+    use std::time::Duration;
+
+    let received = Arc::new(RwLock::new(Vec::new()));
+    let button_received = received.clone();
+    let on_event = OnEvent::new(move |_, event| match event.event_type {
+        EventType::Click
+        | EventType::DoubleClick
+        | EventType::LongPress
+        | EventType::HoverStart => button_received
+            .write()
+            .unwrap()
+            .push(event.event_type.clone()),
+        _ => {}
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles)} on_event={Some(on_event)} />
+            </Element>
+        }
+    });
+    let config = ui.kayak_context.event_config();
+
+    ui.click((50.0, 25.0));
+    ui.click((50.0, 25.0));
+    assert_eq!(
+        received.write().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![EventType::Click, EventType::Click, EventType::DoubleClick]
+    );
+
+    ui.advance_time(config.hover_delay);
+    assert_eq!(
+        received.write().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![EventType::HoverStart]
+    );
+
+    // Clicks too far apart in time aren't double clicks
+    ui.click((50.0, 25.0));
+    ui.advance_time(config.double_click_interval * 2);
+    ui.click((50.0, 25.0));
+    assert_eq!(
+        received.write().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![EventType::Click, EventType::Click]
+    );
+
+    // A long press replaces the click
+    ui.process_events(vec![kayak_ui::core::InputEvent::MouseLeftPress]);
+    ui.advance_time(config.long_press_duration + Duration::from_millis(1));
+    ui.process_events(vec![kayak_ui::core::InputEvent::MouseLeftRelease]);
+    assert_eq!(
+        received.write().unwrap().drain(..).collect::<Vec<_>>(),
+        vec![EventType::LongPress]
+    );
+}
+
+#[test]
+fn test_multi_touch() {
+    use kayak_ui::core::InputEvent;
+    use std::sync::{Arc, RwLock};
+
+    let clicked = Arc::new(RwLock::new(Vec::new()));
+    let make_on_event = |name: &'static str| {
+        let clicked = clicked.clone();
+        OnEvent::new(move |_, event| {
+            if event.event_type == EventType::Click {
+                clicked.write().unwrap().push(name);
+            }
+        })
+    };
+    let (on_left_event, on_right_event) = (make_on_event("left"), make_on_event("right"));
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles.clone())} on_event={Some(on_left_event)} />
+                <Button styles={Some(button_styles)} on_event={Some(on_right_event)} />
+            </Element>
+        }
+    });
+
+    ui.tap((50.0, 25.0));
+    assert_eq!(*clicked.read().unwrap(), vec!["left"]);
+
+    // Both buttons are held at the same time, and the touch on the left one slides off before lifting
+    ui.process_events(vec![
+        InputEvent::TouchStart {
+            id: 1,
+            position: (50.0, 25.0),
+        },
+        InputEvent::TouchStart {
+            id: 2,
+            position: (50.0, 75.0),
+        },
+    ]);
+    ui.process_events(vec![
+        InputEvent::TouchMove {
+            id: 1,
+            position: (500.0, 25.0),
+        },
+        InputEvent::TouchEnd {
+            id: 2,
+            position: (50.0, 75.0),
+        },
+    ]);
+    ui.process_events(vec![InputEvent::TouchEnd {
+        id: 1,
+        position: (500.0, 25.0),
+    }]);
+    assert_eq!(*clicked.read().unwrap(), vec!["left", "right"]);
+}
+
+#[test]
+fn test_touches_are_active_separately() {
+    use kayak_ui::core::{ClassStyle, Color, InputEvent, StyleSheet};
+
+    let white = Color::new(1.0, 1.0, 1.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+    let background = |color: Color| Style {
+        background_color: StyleProp::Value(color),
+        ..Default::default()
+    };
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        context.set_stylesheet(StyleSheet::new().with_class(
+            "button",
+            ClassStyle::new(background(white)).with_active(background(blue)),
+        ));
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles.clone())} />
+                <Button styles={Some(button_styles)} />
+            </Element>
+        }
+    });
+
+    let buttons = ui.find_widgets::<Button>();
+    let background_colors = |ui: &TestContext| {
+        buttons
+            .iter()
+            .map(|button| {
+                let node = ui.kayak_context.widget_manager.get_node(button).unwrap();
+                node.styles.background_color.resolve()
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Two fingers hold both buttons down, then the first one is lifted
+    ui.process_events(vec![
+        InputEvent::TouchStart {
+            id: 1,
+            position: (50.0, 25.0),
+        },
+        InputEvent::TouchStart {
+            id: 2,
+            position: (50.0, 75.0),
+        },
+    ]);
+    assert_eq!(background_colors(&ui), vec![blue, blue]);
+
+    ui.process_events(vec![InputEvent::TouchEnd {
+        id: 1,
+        position: (50.0, 25.0),
+    }]);
+    assert_eq!(background_colors(&ui), vec![white, blue]);
+
+    ui.process_events(vec![InputEvent::TouchCancel { id: 2 }]);
+    assert_eq!(background_colors(&ui), vec![white, white]);
+}
+
+#[test]
+fn test_custom_events_bubble() {
+    use std::sync::{Arc, RwLock};
+
+    struct ItemSelected(u32);
+
+    let selected = Arc::new(RwLock::new(Vec::new()));
+    let list_selected = selected.clone();
+    let on_list_event = OnEvent::new(move |_, event| {
+        if let Some(ItemSelected(item)) = event.custom::<ItemSelected>() {
+            list_selected.write().unwrap().push(*item);
+        }
+    });
+    // Swallows the selection of the first item
+    let on_group_event = OnEvent::new(move |_, event| {
+        if let Some(ItemSelected(0)) = event.custom::<ItemSelected>() {
+            event.stop_propagation();
+        }
+    });
+    let make_on_row_event = |item: u32| {
+        OnEvent::new(move |context, event| {
+            if event.event_type == EventType::Click {
+                context.dispatch_custom_event(event.current_target, ItemSelected(item));
+            }
+        })
+    };
+    let (on_first_event, on_second_event) = (make_on_row_event(0), make_on_row_event(1));
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let row_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element on_event={Some(on_list_event)}>
+                <Element on_event={Some(on_group_event)}>
+                    <Button styles={Some(row_styles.clone())} on_event={Some(on_first_event)} />
+                    <Button styles={Some(row_styles)} on_event={Some(on_second_event)} />
+                </Element>
+            </Element>
+        }
+    });
+
+    let rows = ui.find_widgets::<Button>();
+    ui.click_widget(rows[0]);
+    ui.click_widget(rows[1]);
+    assert_eq!(*selected.read().unwrap(), vec![1]);
+}
+
+/// Shows its children until the state passed to `on_shown_state` is set to false
+#[widget(no_memo)]
+fn Hideable(children: Children, on_shown_state: Handler<Binding<bool>>) {
+    let shown = context.create_state(true).unwrap();
+    on_shown_state.call(shown.clone());
+
+    rsx! {
+        <If condition={shown.get()}>
+            {children}
+        </If>
+    }
+}
+
+#[test]
+fn test_custom_events_skip_unmounted_targets() {
+    use kayak_ui::core::MutableBound;
+    use std::sync::{Arc, RwLock};
+
+    struct Ping;
+
+    let pings = Arc::new(RwLock::new(0));
+    let list_pings = pings.clone();
+    let on_list_event = OnEvent::new(move |_, event| {
+        if event.custom::<Ping>().is_some() {
+            *list_pings.write().unwrap() += 1;
+        }
+    });
+    let shown_state = Arc::new(RwLock::new(None));
+    let widget_shown_state = shown_state.clone();
+    let on_shown_state = Handler::new(move |shown: Binding<bool>| {
+        *widget_shown_state.write().unwrap() = Some(shown);
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element on_event={Some(on_list_event)}>
+                <Hideable on_shown_state={on_shown_state}>
+                    <Button styles={Some(button_styles)} />
+                </Hideable>
+            </Element>
+        }
+    });
+
+    let button = ui.find_widget::<Button>().unwrap();
+    ui.kayak_context.dispatch_custom_event(button, Ping);
+    ui.process_events(Vec::new());
+    assert_eq!(*pings.read().unwrap(), 1);
+
+    // The button is unmounted after the event was queued, so the event is dropped
+    ui.kayak_context.dispatch_custom_event(button, Ping);
+    shown_state.read().unwrap().as_ref().unwrap().set(false);
+    ui.render();
+    assert_eq!(ui.find_widget::<Button>(), None);
+    ui.process_events(Vec::new());
+    assert_eq!(*pings.read().unwrap(), 1);
+}
+
+static TRACKED_UNMOUNTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[widget]
+fn Tracked() {
+    let (count, ..) = use_state!(0);
+    context.create_provider(count);
+    use_effect!(|| {}, []);
+    context.on_unmount(|| {
+        TRACKED_UNMOUNTS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    });
+
+    rsx! {
+        <Background />
+    }
+}
+
+#[test]
+fn test_unmount_releases_widget_data() {
+    use kayak_ui::core::MutableBound;
+    use std::sync::{atomic::Ordering, Arc, RwLock};
+
+    let shown_state = Arc::new(RwLock::new(None));
+    let widget_shown_state = shown_state.clone();
+    let on_shown_state = Handler::new(move |shown: Binding<bool>| {
+        *widget_shown_state.write().unwrap() = Some(shown);
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <Hideable on_shown_state={on_shown_state}>
+                <Tracked />
+            </Hideable>
+        }
+    });
+
+    let tracked = ui.find_widget_by_name("Tracked").unwrap();
+    ui.kayak_context.capture_pointer(tracked);
+    assert!(ui.has_widget_data(tracked));
+    assert_eq!(TRACKED_UNMOUNTS.load(Ordering::SeqCst), 0);
+
+    shown_state.read().unwrap().as_ref().unwrap().set(false);
+    ui.render();
+
+    assert_eq!(ui.find_widget_by_name("Tracked"), None);
+    assert_eq!(TRACKED_UNMOUNTS.load(Ordering::SeqCst), 1);
+    assert!(!ui.has_widget_data(tracked));
+    assert_eq!(ui.kayak_context.pointer_capture(), None);
+}
+
+static EFFECT_RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+static EFFECT_CLEANUPS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[widget]
+fn EffectCounter() {
+    let (count, set_count, raw_count) = use_state!(0);
+    let on_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::Click {
+            set_count(count + 1);
+        }
+    });
+    use_effect!(
+        || {
+            EFFECT_RUNS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            || {
+                EFFECT_CLEANUPS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        },
+        [raw_count]
+    );
+
+    let button_styles = Style {
+        width: StyleProp::Value(Units::Pixels(100.0)),
+        height: StyleProp::Value(Units::Pixels(50.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <Button styles={Some(button_styles)} on_event={Some(on_event)} />
+    }
+}
+
+#[test]
+fn test_effect_cleanup_runs_on_change_and_unmount() {
+    use kayak_ui::core::MutableBound;
+    use std::sync::{atomic::Ordering, Arc, RwLock};
+
+    let shown_state = Arc::new(RwLock::new(None));
+    let widget_shown_state = shown_state.clone();
+    let on_shown_state = Handler::new(move |shown: Binding<bool>| {
+        *widget_shown_state.write().unwrap() = Some(shown);
+    });
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <Hideable on_shown_state={on_shown_state}>
+                <EffectCounter />
+            </Hideable>
+        }
+    });
+    let effect_calls = || {
+        (
+            EFFECT_RUNS.load(Ordering::SeqCst),
+            EFFECT_CLEANUPS.load(Ordering::SeqCst),
+        )
+    };
+    assert_eq!(effect_calls(), (1, 0));
+
+    // The previous run is cleaned up before the effect runs again
+    let button = ui.find_widget::<Button>().unwrap();
+    ui.click_widget(button);
+    assert_eq!(effect_calls(), (2, 1));
+
+    // Unmounting only cleans up the last run
+    shown_state.read().unwrap().as_ref().unwrap().set(false);
+    ui.render();
+    assert_eq!(effect_calls(), (2, 2));
+}
+
+#[test]
+fn test_cursor_icon() {
+    use kayak_ui::core::CursorIcon;
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        let handle_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            cursor: StyleProp::Value(CursorIcon::Grab),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles)} />
+                <Element styles={Some(handle_styles)}>
+                    <Background />
+                </Element>
+            </Element>
+        }
+    });
+
+    ui.move_mouse((50.0, 25.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Pointer);
+    // Inherited by the background
+    ui.move_mouse((50.0, 75.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Grab);
+    ui.move_mouse((500.0, 500.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Default);
+}
+
+#[test]
+fn test_styles_inherit_through_the_tree() {
+    use kayak_ui::core::Color;
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let root_styles = Style {
+            color: StyleProp::Value(Color::new(1.0, 0.0, 0.0, 1.0)),
+            ..Default::default()
+        };
+        let middle_styles = Style {
+            min_width: StyleProp::Value(Units::Pixels(10.0)),
+            ..Default::default()
+        };
+        let leaf_styles = Style {
+            min_width: StyleProp::Inherit,
+            ..Default::default()
+        };
+        render! {
+            <Element styles={Some(root_styles)}>
+                <Element styles={Some(middle_styles)}>
+                    <Background styles={Some(leaf_styles)} />
+                </Element>
+            </Element>
+        }
+    });
+
+    let leaf = ui.find_widget_by_name("Background").unwrap();
+    let styles = &ui
+        .kayak_context
+        .widget_manager
+        .get_node(&leaf)
+        .unwrap()
+        .styles;
+    assert_eq!(styles.color.resolve(), Color::new(1.0, 0.0, 0.0, 1.0));
+    assert_eq!(styles.min_width.resolve(), Units::Pixels(10.0));
+}
+
+#[test]
+fn test_stylesheet_state_variants() {
+    use kayak_ui::core::{ClassStyle, Color, InputEvent, StyleSheet};
+
+    let white = Color::new(1.0, 1.0, 1.0, 1.0);
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+    let gray = Color::new(0.5, 0.5, 0.5, 1.0);
+    let background = |color: Color| Style {
+        background_color: StyleProp::Value(color),
+        ..Default::default()
+    };
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        context.set_stylesheet(
+            StyleSheet::new()
+                .with_class(
+                    "button",
+                    ClassStyle::new(background(white))
+                        .with_hover(background(red))
+                        .with_active(background(blue))
+                        .with_disabled(background(gray)),
+                )
+                .with_class(
+                    "tall",
+                    ClassStyle::new(Style {
+                        height: StyleProp::Value(Units::Pixels(90.0)),
+                        ..Default::default()
+                    }),
+                ),
+        );
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles.clone())} />
+                <Button styles={Some(button_styles)} class={Some("tall".to_string())} disabled={true} />
+            </Element>
+        }
+    });
+
+    let buttons = ui.find_widgets::<Button>();
+    let background_color = |ui: &TestContext, index: Index| {
+        let node = ui.kayak_context.widget_manager.get_node(&index).unwrap();
+        node.styles.background_color.resolve()
+    };
+    assert_eq!(background_color(&ui, buttons[0]), white);
+    assert_eq!(background_color(&ui, buttons[1]), gray);
+    // Styles passed to the widget win over its classes
+    assert_eq!(ui.get_layout(buttons[1]).unwrap().height, 50.0);
+
+    ui.move_mouse((50.0, 25.0));
+    assert_eq!(background_color(&ui, buttons[0]), red);
+    ui.process_events(vec![InputEvent::MouseLeftPress]);
+    assert_eq!(background_color(&ui, buttons[0]), blue);
+    ui.process_events(vec![InputEvent::MouseLeftRelease]);
+    assert_eq!(background_color(&ui, buttons[0]), red);
+    ui.move_mouse((500.0, 500.0));
+    assert_eq!(background_color(&ui, buttons[0]), white);
+
+    // Disabled widgets can't be focused
+    ui.press_key(kayak_ui::core::KeyCode::Tab);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[0]));
+    ui.press_key(kayak_ui::core::KeyCode::Tab);
+    assert_eq!(ui.kayak_context.current_focus(), Some(buttons[0]));
+}
+
+#[widget]
+fn Unstyled() {
+    rsx! {
+        <Background />
+    }
+}
+
+#[test]
+fn test_render_command_from_class() {
+    use kayak_ui::core::{
+        render_command::RenderCommand, render_primitive::RenderPrimitive, Background as Fill,
+        ClassStyle, Color, StyleSheet,
+    };
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let ui = TestContext::new((800.0, 600.0), |context| {
+        context.set_stylesheet(StyleSheet::new().with_class(
+            "panel",
+            ClassStyle::new(Style {
+                render_command: StyleProp::Value(RenderCommand::Quad),
+                background_color: StyleProp::Value(red),
+                width: StyleProp::Value(Units::Pixels(100.0)),
+                height: StyleProp::Value(Units::Pixels(50.0)),
+                padding_left: StyleProp::Value(Units::Pixels(10.0)),
+                ..Default::default()
+            }),
+        ));
+        render! {
+            <Element>
+                <Unstyled class={Some("panel".to_string())} />
+            </Element>
+        }
+    });
+
+    // The widget only gets its render command from its class, but still has a node laid out like any other
+    let panel = ui.find_widget_by_name("Unstyled").unwrap();
+    let layout = ui.get_layout(panel).unwrap();
+    assert_eq!((layout.width, layout.height), (100.0, 50.0));
+    let background = ui.find_widget::<Background>().unwrap();
+    assert_eq!(ui.get_layout(background).unwrap().posx, 10.0);
+    assert!(ui
+        .build_render_primitives()
+        .iter()
+        .any(|primitive| matches!(
+            primitive,
+            RenderPrimitive::Quad { background, .. } if *background == Fill::Color(red)
+        )));
+}
+
+#[test]
+fn test_reload_stylesheet() {
+    use kayak_ui::core::parse_stylesheet;
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        context.set_stylesheet(parse_stylesheet("panel { width: 100px; height: 50px; }").unwrap());
+        render! {
+            <Element>
+                <Background class={Some("panel".to_string())} />
+            </Element>
+        }
+    });
+
+    let panel = ui.find_widget_by_name("Background").unwrap();
+    assert_eq!(ui.get_layout(panel).unwrap().height, 50.0);
+
+    ui.kayak_context
+        .set_stylesheet(parse_stylesheet("panel { width: 100px; height: 80px; }").unwrap());
+    ui.render();
+    assert_eq!(ui.get_layout(panel).unwrap().height, 80.0);
+}
+
+#[test]
+fn test_stylesheet_applies_over_default_styles() {
+    use kayak_ui::core::{ClassStyle, Color, CursorIcon, StyleSheet};
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        context.set_stylesheet(
+            StyleSheet::new()
+                .with_class(
+                    "button",
+                    ClassStyle::new(Style {
+                        background_color: StyleProp::Value(red),
+                        ..Default::default()
+                    }),
+                )
+                .with_class(
+                    "handle",
+                    ClassStyle::new(Style {
+                        width: StyleProp::Value(Units::Pixels(100.0)),
+                        height: StyleProp::Value(Units::Pixels(50.0)),
+                        cursor: StyleProp::Value(CursorIcon::Grab),
+                        ..Default::default()
+                    }),
+                ),
+        );
+        render! {
+            <Element>
+                <Button />
+                <Element class={Some("handle".to_string())}>
+                    <Background />
+                </Element>
+            </Element>
+        }
+    });
+
+    // The built-in widgets are styled without an App, and the user's class only overrides what it sets
+    let button = ui.find_widget::<Button>().unwrap();
+    let node = ui.kayak_context.widget_manager.get_node(&button).unwrap();
+    assert_eq!(node.styles.background_color.resolve(), red);
+    assert_eq!(ui.get_layout(button).unwrap().height, 45.0);
+
+    // The cursor set by a class is inherited by the background
+    let handle = ui
+        .get_layout(ui.find_widget_by_name("Background").unwrap())
+        .unwrap();
+    ui.move_mouse((handle.posx + 50.0, handle.posy + 25.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Grab);
+}
+
+#[test]
+fn test_widget_resets_class_property() {
+    use kayak_ui::core::{ClassStyle, StyleSheet};
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        context.set_stylesheet(StyleSheet::new().with_class(
+            "panel",
+            ClassStyle::new(Style {
+                width: StyleProp::Value(Units::Pixels(100.0)),
+                height: StyleProp::Value(Units::Pixels(50.0)),
+                ..Default::default()
+            }),
+        ));
+        // Setting `StyleProp::Default` wouldn't override the class, the value has to be explicit
+        let panel_styles = Style {
+            width: StyleProp::Value(Units::Stretch(1.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Background styles={Some(panel_styles)} class={Some("panel".to_string())} />
+            </Element>
+        }
+    });
+
+    let panel = ui.find_widget_by_name("Background").unwrap();
+    let node = ui.kayak_context.widget_manager.get_node(&panel).unwrap();
+    assert_eq!(node.styles.width, StyleProp::Value(Units::Stretch(1.0)));
+    assert_eq!(node.styles.height, StyleProp::Value(Units::Pixels(50.0)));
+    assert_eq!(ui.get_layout(panel).unwrap().width, 800.0);
+}
+
+/// Returns the path of a snapshot stored in `tests/snapshots`
+fn snapshot_path(name: &str) -> String {
+    format!(
+        "{}/tests/snapshots/{}.snap",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    )
+}
+
+#[test]
+fn test_quad_borders() {
+    use kayak_ui::core::{render_primitive::RenderPrimitive, snapshot::assert_snapshot, Color};
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let ui = TestContext::new((800.0, 600.0), |context| {
+        let bordered_styles = Style {
+            background_color: StyleProp::Value(Color::WHITE),
+            border: StyleProp::Value((1.0, 2.0, 3.0, 4.0)),
+            border_color: StyleProp::Value(red),
+            border_radius: StyleProp::Value((5.0, 5.0, 5.0, 5.0)),
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        let plain_styles = Style {
+            background_color: StyleProp::Value(Color::new(0.0, 0.0, 1.0, 1.0)),
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Background styles={Some(bordered_styles)} />
+                <Background styles={Some(plain_styles)} />
+            </Element>
+        }
+    });
+
+    let borders: Vec<_> = ui
+        .build_render_primitives()
+        .into_iter()
+        .filter_map(|primitive| match primitive {
+            RenderPrimitive::Quad {
+                border,
+                border_color,
+                ..
+            } => Some((border, border_color)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        borders,
+        vec![
+            ((1.0, 2.0, 3.0, 4.0), red),
+            ((0.0, 0.0, 0.0, 0.0), Color::TRANSPARENT)
+        ]
+    );
+    assert_snapshot(snapshot_path("quad_borders"), &ui.snapshot());
+}
+
+#[test]
+fn test_box_shadows() {
+    use kayak_ui::core::{
+        render_primitive::RenderPrimitive, snapshot::assert_snapshot, BoxShadow, Color,
+    };
+
+    let ui = TestContext::new((800.0, 600.0), |context| {
+        let quad_styles = |box_shadow: BoxShadow| Style {
+            background_color: StyleProp::Value(Color::WHITE),
+            box_shadow: StyleProp::Value(Some(box_shadow)),
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        let drop_shadow = BoxShadow {
+            offset: (2.0, 3.0),
+            blur: 4.0,
+            spread: 1.0,
+            color: Color::new(0.0, 0.0, 0.0, 0.5),
+            inset: false,
+        };
+        let inset_shadow = BoxShadow {
+            blur: 2.0,
+            color: Color::new(1.0, 0.0, 0.0, 1.0),
+            inset: true,
+            ..BoxShadow::default()
+        };
+        let drop_shadow_styles = quad_styles(drop_shadow);
+        let inset_shadow_styles = quad_styles(inset_shadow);
+        render! {
+            <Element>
+                <Background styles={Some(drop_shadow_styles)} />
+                <Background styles={Some(inset_shadow_styles)} />
+            </Element>
+        }
+    });
+
+    // Each shadow follows its quad, right behind it or right above it when inset
+    let z_indices: Vec<_> = ui
+        .build_render_primitives()
+        .into_iter()
+        .filter_map(|primitive| match primitive {
+            RenderPrimitive::Quad { layout, .. } => Some(("quad", layout.z_index)),
+            RenderPrimitive::BoxShadow {
+                layout, box_shadow, ..
+            } => Some((
+                if box_shadow.inset { "inset" } else { "shadow" },
+                layout.z_index,
+            )),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        z_indices,
+        vec![
+            ("quad", 1.0),
+            ("shadow", 0.99),
+            ("quad", 1.0),
+            ("inset", 1.01)
+        ]
+    );
+    assert_snapshot(snapshot_path("box_shadows"), &ui.snapshot());
+}
+
+#[test]
+fn test_gradient_backgrounds() {
+    use kayak_ui::core::{
+        render_primitive::RenderPrimitive, snapshot::assert_snapshot, Background as Fill, Color,
+        ColorStop,
+    };
+
+    let red = Color::new(1.0, 0.0, 0.0, 1.0);
+    let blue = Color::new(0.0, 0.0, 1.0, 1.0);
+    let stops = vec![ColorStop::new(red, 0.0), ColorStop::new(blue, 1.0)];
+    let linear_gradient = Fill::LinearGradient {
+        angle: 90.0,
+        stops: stops.clone(),
+    };
+    let radial_gradient = Fill::RadialGradient {
+        center: (0.5, 0.5),
+        stops,
+    };
+
+    let backgrounds = vec![linear_gradient.clone(), radial_gradient.clone()];
+    let ui = TestContext::new((800.0, 600.0), |context| {
+        // The gradient is drawn instead of the background color
+        let quad_styles = |background: Fill| Style {
+            background: StyleProp::Value(background),
+            background_color: StyleProp::Value(Color::WHITE),
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        let linear_styles = quad_styles(linear_gradient);
+        let radial_styles = quad_styles(radial_gradient);
+        render! {
+            <Element>
+                <Background styles={Some(linear_styles)} />
+                <Background styles={Some(radial_styles)} />
+            </Element>
+        }
+    });
+
+    let quad_backgrounds: Vec<_> = ui
+        .build_render_primitives()
+        .into_iter()
+        .filter_map(|primitive| match primitive {
+            RenderPrimitive::Quad { background, .. } => Some(background),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(quad_backgrounds, backgrounds);
+    assert_snapshot(snapshot_path("gradient_backgrounds"), &ui.snapshot());
+}
+
+#[test]
+fn test_opacity_composes_down_the_tree() {
+    use kayak_ui::core::{render_primitive::RenderPrimitive, Background as Fill, Color};
+
+    let ui = TestContext::new((800.0, 600.0), |context| {
+        let faded_styles = Style {
+            opacity: StyleProp::Value(0.5),
+            ..Default::default()
+        };
+        let background_styles = Style {
+            background_color: StyleProp::Value(Color::new(1.0, 0.0, 0.0, 1.0)),
+            width: StyleProp::Value(Units::Pixels(10.0)),
+            height: StyleProp::Value(Units::Pixels(10.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element styles={Some(faded_styles.clone())}>
+                <Element styles={Some(faded_styles)}>
+                    <Background styles={Some(background_styles)} />
+                </Element>
+            </Element>
+        }
+    });
+
+    let background = ui
+        .build_render_primitives()
+        .into_iter()
+        .find_map(|primitive| match primitive {
+            RenderPrimitive::Quad { background, .. } => Some(background),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(background, Fill::Color(Color::new(1.0, 0.0, 0.0, 0.25)));
+}
+
+#[test]
+fn test_transform_affects_hit_testing() {
+    use kayak_ui::core::{render_primitive::RenderPrimitive, CursorIcon, Transform};
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            transform: StyleProp::Value(Transform {
+                translation: (200.0, 0.0),
+                rotation: 90.0,
+                ..Transform::default()
+            }),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Button styles={Some(button_styles)} />
+            </Element>
+        }
+    });
+
+    // The layout is left untouched
+    let button = ui.find_widget::<Button>().unwrap();
+    let layout = ui.get_layout(button).unwrap();
+    assert_eq!((layout.posx, layout.posy), (0.0, 0.0));
+    assert!(ui
+        .build_render_primitives()
+        .iter()
+        .any(|primitive| matches!(primitive, RenderPrimitive::Transformed { .. })));
+
+    // Rotated around its center and moved, the button covers 225..275 horizontally and -25..75 vertically
+    ui.move_mouse((250.0, 60.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Pointer);
+    ui.move_mouse((210.0, 25.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Default);
+    ui.move_mouse((50.0, 25.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Default);
+}
+
+#[test]
+fn test_transform_applies_to_children() {
+    use kayak_ui::core::{render_primitive::RenderPrimitive, CursorIcon, Transform};
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        let parent_styles = Style {
+            transform: StyleProp::Value(Transform {
+                translation: (100.0, 50.0),
+                scale: (2.0, 2.0),
+                origin: (0.0, 0.0),
+                ..Transform::default()
+            }),
+            ..Default::default()
+        };
+        let button_styles = Style {
+            width: StyleProp::Value(Units::Pixels(100.0)),
+            height: StyleProp::Value(Units::Pixels(50.0)),
+            ..Default::default()
+        };
+        render! {
+            <Element>
+                <Element styles={Some(parent_styles)}>
+                    <Button styles={Some(button_styles)} />
+                </Element>
+            </Element>
+        }
+    });
+
+    // The parent's transform is applied to the quad of the button, whose layout is left untouched
+    let button = ui.find_widget::<Button>().unwrap();
+    let layout = ui.get_layout(button).unwrap();
+    assert_eq!((layout.posx, layout.posy), (0.0, 0.0));
+    let drawn = ui
+        .build_render_primitives()
+        .into_iter()
+        .find_map(|primitive| match primitive {
+            RenderPrimitive::Transformed {
+                transform,
+                primitive,
+            } => match *primitive {
+                RenderPrimitive::Quad { layout, .. } => Some(transform.transform_rect(&layout)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        (drawn.posx, drawn.posy, drawn.width, drawn.height),
+        (100.0, 50.0, 200.0, 100.0)
+    );
+
+    // The button is hovered where it's drawn, not where it was laid out
+    ui.move_mouse((250.0, 125.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Pointer);
+    ui.move_mouse((50.0, 25.0));
+    assert_eq!(ui.kayak_context.cursor_icon(), CursorIcon::Default);
+}
+
+#[widget(no_memo)]
+fn CounterRow(row_id: usize, on_remove: Handler<usize>) {
+    let on_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::RightClick {
+            on_remove.call(row_id);
+        }
+    });
+
+    rsx! {
+        <Element on_event={Some(on_event)}>
+            <Counter />
+        </Element>
+    }
+}
+
+#[widget]
+fn CounterRows() {
+    let (rows, set_rows, ..) = use_state!(vec![0usize, 1, 2]);
+    let remaining_rows = rows.clone();
+    let on_remove = Handler::new(move |row_id: usize| {
+        set_rows(
+            remaining_rows
+                .iter()
+                .copied()
+                .filter(|row| *row != row_id)
+                .collect(),
+        );
+    });
+
+    rsx! {
+        <Element>
+            {VecTracker::from(
+                rows
+                    .clone()
+                    .into_iter()
+                    .map(|row_id| constructor! { <CounterRow row_id={row_id} on_remove={on_remove.clone()} /> }),
+            ).with_key(|row: &CounterRow| row.row_id.to_string())}
+        </Element>
+    }
+}
+
+#[test]
+fn test_keyed_children_keep_their_state() {
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <CounterRows />
+        }
+    });
+
+    let buttons = ui.find_widgets::<Button>();
+    assert_eq!(buttons.len(), 3);
+    ui.click_widget(buttons[0]);
+    ui.click_widget(buttons[2]);
+    ui.click_widget(buttons[2]);
+
+    let bar_widths = |ui: &TestContext| {
+        ui.find_widgets_by_name("Background")
+            .into_iter()
+            .map(|bar| ui.get_layout(bar).unwrap().width)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(bar_widths(&ui), vec![20.0, 10.0, 30.0]);
+
+    // Removing the middle row must not move its state onto the last one
+    let middle = ui.get_layout(buttons[1]).unwrap();
+    ui.right_click((middle.posx + 1.0, middle.posy + 1.0));
+
+    assert_eq!(ui.find_widgets::<Button>(), vec![buttons[0], buttons[2]]);
+    assert_eq!(bar_widths(&ui), vec![20.0, 30.0]);
+
+    // The rows were rendered again with a handler that knows the middle row is gone
+    let first = ui.get_layout(buttons[0]).unwrap();
+    ui.right_click((first.posx + 1.0, first.posy + 1.0));
+
+    assert_eq!(ui.find_widgets::<Button>(), vec![buttons[2]]);
+    assert_eq!(bar_widths(&ui), vec![30.0]);
+}
+
+#[widget]
+fn DuplicateKeyCounters() {
+    let (renders, set_renders, ..) = use_state!(0);
+    let on_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::Click {
+            set_renders(renders + 1);
+        }
+    });
+
+    let button_styles = Style {
+        width: StyleProp::Value(Units::Pixels(100.0)),
+        height: StyleProp::Value(Units::Pixels(50.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <>
+            <Button styles={Some(button_styles)} on_event={Some(on_event)} />
+            <Counter key={"counter"} />
+            <Counter key={"counter"} />
+        </>
+    }
+}
+
+#[test]
+fn test_duplicate_keys_keep_separate_widgets() {
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <DuplicateKeyCounters />
+        }
+    });
+
+    let counters = ui.find_widgets_by_name("Counter");
+    assert_eq!(counters.len(), 2);
+    assert_ne!(counters[0], counters[1]);
+
+    let buttons = ui.find_widgets::<Button>();
+    ui.click_widget(buttons[2]);
+    ui.click_widget(buttons[2]);
+
+    let bar_widths = |ui: &TestContext| {
+        ui.find_widgets_by_name("Background")
+            .into_iter()
+            .map(|bar| ui.get_layout(bar).unwrap().width)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(bar_widths(&ui), vec![10.0, 30.0]);
+
+    // Rendering the parent again matches the duplicates in order instead of merging them
+    ui.click_widget(buttons[0]);
+    assert_eq!(ui.find_widgets_by_name("Counter"), counters);
+    assert_eq!(bar_widths(&ui), vec![10.0, 30.0]);
+}
+
+static MEMO_BAR_RENDERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+static PLAIN_BAR_RENDERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[widget]
+fn MemoBar(width: f32) {
+    MEMO_BAR_RENDERS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let bar_styles = Style {
+        width: StyleProp::Value(Units::Pixels(width)),
+        height: StyleProp::Value(Units::Pixels(10.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <Background styles={Some(bar_styles)} />
+    }
+}
+
+#[widget(no_memo)]
+fn PlainBar(width: f32) {
+    PLAIN_BAR_RENDERS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let bar_styles = Style {
+        width: StyleProp::Value(Units::Pixels(width)),
+        height: StyleProp::Value(Units::Pixels(10.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <Background styles={Some(bar_styles)} />
+    }
+}
+
+#[widget]
+fn MemoBars() {
+    let (count, set_count, ..) = use_state!(0.0f32);
+    let on_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::Click {
+            set_count(count + 1.0);
+        }
+    });
+
+    let button_styles = Style {
+        width: StyleProp::Value(Units::Pixels(100.0)),
+        height: StyleProp::Value(Units::Pixels(50.0)),
+        ..Default::default()
+    };
+
+    rsx! {
+        <>
+            <Button styles={Some(button_styles)} on_event={Some(on_event)} />
+            <MemoBar width={10.0} />
+            <MemoBar width={10.0 + count} />
+            <PlainBar width={10.0} />
+        </>
+    }
+}
+
+#[test]
+fn test_memoized_widgets_skip_unchanged_renders() {
+    use std::sync::atomic::Ordering;
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <Element>
+                <MemoBars />
+            </Element>
+        }
+    });
+    assert_eq!(MEMO_BAR_RENDERS.load(Ordering::SeqCst), 2);
+    assert_eq!(PLAIN_BAR_RENDERS.load(Ordering::SeqCst), 1);
+
+    let button = ui.find_widget::<Button>().unwrap();
+    ui.click_widget(button);
+
+    // Only the bar whose width changed is rendered again, unless the widget opted out of memoization
+    assert_eq!(MEMO_BAR_RENDERS.load(Ordering::SeqCst), 3);
+    assert_eq!(PLAIN_BAR_RENDERS.load(Ordering::SeqCst), 2);
+    let bar_widths: Vec<_> = ui
+        .find_widgets_by_name("Background")
+        .into_iter()
+        .map(|bar| ui.get_layout(bar).unwrap().width)
+        .collect();
+    assert_eq!(bar_widths, vec![10.0, 11.0, 10.0]);
+}
+
+#[widget]
+fn MemoLabel() {
+    rsx! {
+        <Element>
+            <Text size={16.0} content={"Label".to_string()} />
+        </Element>
+    }
+}
+
+#[widget]
+fn ThemedLabel() {
+    use kayak_ui::core::Color;
+
+    let (dark, set_dark, ..) = use_state!(false);
+    let on_event = OnEvent::new(move |_, event| {
+        if event.event_type == EventType::Click {
+            set_dark(!dark);
+        }
+    });
+
+    let button_styles = Style {
+        width: StyleProp::Value(Units::Pixels(100.0)),
+        height: StyleProp::Value(Units::Pixels(50.0)),
+        ..Default::default()
+    };
+    let theme_styles = Style {
+        color: StyleProp::Value(if dark { Color::BLACK } else { Color::WHITE }),
+        ..Default::default()
+    };
+
+    rsx! {
+        <>
+            <Button styles={Some(button_styles)} on_event={Some(on_event)} />
+            <Element styles={Some(theme_styles)}>
+                <MemoLabel />
+            </Element>
+        </>
+    }
+}
+
+#[test]
+fn test_memoized_widgets_inherit_changed_styles() {
+    use kayak_ui::core::{render_primitive::RenderPrimitive, Color};
+
+    let mut ui = TestContext::new((800.0, 600.0), |context| {
+        render! {
+            <ThemedLabel />
+        }
+    });
+    let text_color = |ui: &TestContext| {
+        ui.build_render_primitives()
+            .into_iter()
+            .find_map(|primitive| match primitive {
+                RenderPrimitive::Text { color, .. } => Some(color),
+                _ => None,
+            })
+            .unwrap()
+    };
+    assert_eq!(text_color(&ui), Color::WHITE);
+
+    // The label skips rendering, but the text it rendered still picks up the new color
+    let button = ui.find_widget::<Button>().unwrap();
+    ui.click_widget(button);
+    assert_eq!(text_color(&ui), Color::BLACK);
+}